@@ -0,0 +1,415 @@
+//! Derive macros for the [`ToLink`] and [`FromLink`] traits of the
+//! [`wstp`](https://crates.io/crates/wstp) crate.
+//!
+//! This crate should not be used directly. Instead, enable the `derive` feature of
+//! `wstp`, and use the macros re-exported as `wstp::ToLink` and `wstp::FromLink`.
+//!
+//! [`ToLink`]: https://docs.rs/wstp/latest/wstp/trait.ToLink.html
+//! [`FromLink`]: https://docs.rs/wstp/latest/wstp/trait.FromLink.html
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    ext::IdentExt, parse_macro_input, parse_quote, Data, DeriveInput, Fields, Generics,
+    Ident, LitStr,
+};
+
+/// Derive `wstp::ToLink`.
+#[proc_macro_derive(ToLink, attributes(wstp))]
+pub fn derive_to_link(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    to_link_impl(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `wstp::FromLink`.
+#[proc_macro_derive(FromLink, attributes(wstp))]
+pub fn derive_from_link(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    from_link_impl(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+//======================================
+// ToLink
+//======================================
+
+fn to_link_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    let context = parse_context(&input)?;
+
+    let DeriveInput {
+        ident,
+        generics,
+        data,
+        ..
+    } = input;
+
+    let body: TokenStream = match data {
+        Data::Struct(data) => {
+            let symbol = format!("{}{}", context, ident.unraw());
+
+            match data.fields {
+                Fields::Named(fields) => {
+                    let names: Vec<&Ident> = fields
+                        .named
+                        .iter()
+                        .map(|field| field.ident.as_ref().unwrap())
+                        .collect();
+
+                    put_association(&names, |name| quote! { &self.#name })
+                },
+                Fields::Unnamed(fields) => {
+                    let count = fields.unnamed.len();
+                    let indices = (0..count).map(syn::Index::from);
+
+                    quote! {
+                        link.put_function(#symbol, #count)?;
+                        #( ::wstp::ToLink::to_link(&self.#indices, link)?; )*
+                    }
+                },
+                Fields::Unit => quote! { link.put_symbol(#symbol)?; },
+            }
+        },
+        Data::Enum(data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let variant_ident = &variant.ident;
+                let symbol = format!("{}{}", context, variant_ident.unraw());
+
+                match &variant.fields {
+                    Fields::Named(fields) => {
+                        let names: Vec<&Ident> = fields
+                            .named
+                            .iter()
+                            .map(|field| field.ident.as_ref().unwrap())
+                            .collect();
+
+                        let bindings: Vec<Ident> = names
+                            .iter()
+                            .map(|name| format_ident!("field_{}", name))
+                            .collect();
+
+                        let put = put_association(&names, |name| {
+                            let binding = format_ident!("field_{}", name);
+                            quote! { #binding }
+                        });
+
+                        quote! {
+                            Self::#variant_ident { #(#names: #bindings),* } => {
+                                link.put_function(#symbol, 1)?;
+                                #put
+                            }
+                        }
+                    },
+                    Fields::Unnamed(fields) => {
+                        let count = fields.unnamed.len();
+                        let bindings: Vec<Ident> =
+                            (0..count).map(|i| format_ident!("field_{}", i)).collect();
+
+                        quote! {
+                            Self::#variant_ident( #(#bindings),* ) => {
+                                link.put_function(#symbol, #count)?;
+                                #( ::wstp::ToLink::to_link(#bindings, link)?; )*
+                            }
+                        }
+                    },
+                    Fields::Unit => quote! {
+                        Self::#variant_ident => link.put_symbol(#symbol)?,
+                    },
+                }
+            });
+
+            if data.variants.is_empty() {
+                quote! { match *self {} }
+            } else {
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        },
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span,
+                "ToLink cannot be derived for unions",
+            ))
+        },
+    };
+
+    let generics = add_trait_bounds(generics, parse_quote!(::wstp::ToLink));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::wstp::ToLink for #ident #ty_generics #where_clause {
+            fn to_link(
+                &self,
+                link: &mut ::wstp::Link,
+            ) -> ::std::result::Result<(), ::wstp::Error> {
+                #body
+
+                ::std::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+/// Generate code that writes `<|"name" -> value, ..|>` for each of `names`.
+fn put_association<F>(names: &[&Ident], value: F) -> TokenStream
+where
+    F: Fn(&Ident) -> TokenStream,
+{
+    let count = names.len();
+    let keys = names.iter().map(|name| name.unraw().to_string());
+    let values = names.iter().map(|name| value(name));
+
+    quote! {
+        link.put_function("System`Association", #count)?;
+        #(
+            ::wstp::__private::put_rule_key(link, #keys)?;
+            ::wstp::ToLink::to_link(#values, link)?;
+        )*
+    }
+}
+
+//======================================
+// FromLink
+//======================================
+
+fn from_link_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    let context = parse_context(&input)?;
+
+    let DeriveInput {
+        ident,
+        generics,
+        data,
+        ..
+    } = input;
+
+    let type_name = ident.unraw().to_string();
+
+    let body: TokenStream = match data {
+        Data::Struct(data) => match data.fields {
+            Fields::Named(fields) => {
+                let names: Vec<&Ident> = fields
+                    .named
+                    .iter()
+                    .map(|field| field.ident.as_ref().unwrap())
+                    .collect();
+
+                get_association(&type_name, &quote! { Self }, &names)
+            },
+            Fields::Unnamed(fields) => {
+                let count = fields.unnamed.len();
+                let fields =
+                    (0..count).map(|_| quote! { ::wstp::FromLink::from_link(link)? });
+
+                quote! {
+                    let (name, arg_count) =
+                        ::wstp::__private::get_name(link, #type_name, #context)?;
+
+                    if name != #type_name {
+                        return ::std::result::Result::Err(
+                            ::wstp::__private::unknown_variant(#type_name, &name)
+                        );
+                    }
+
+                    ::wstp::__private::check_arg_count(#type_name, #count, arg_count)?;
+
+                    ::std::result::Result::Ok(Self( #(#fields),* ))
+                }
+            },
+            Fields::Unit => quote! {
+                let (name, arg_count) =
+                    ::wstp::__private::get_name(link, #type_name, #context)?;
+
+                if name != #type_name || arg_count.is_some() {
+                    return ::std::result::Result::Err(
+                        ::wstp::__private::unknown_variant(#type_name, &name)
+                    );
+                }
+
+                ::std::result::Result::Ok(Self)
+            },
+        },
+        Data::Enum(data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let variant_ident = &variant.ident;
+                let variant_name = variant_ident.unraw().to_string();
+
+                match &variant.fields {
+                    Fields::Named(fields) => {
+                        let names: Vec<&Ident> = fields
+                            .named
+                            .iter()
+                            .map(|field| field.ident.as_ref().unwrap())
+                            .collect();
+
+                        let get = get_association(
+                            &format!("{}::{}", type_name, variant_name),
+                            &quote! { Self::#variant_ident },
+                            &names,
+                        );
+
+                        quote! {
+                            #variant_name if arg_count.is_some() => {
+                                ::wstp::__private::check_arg_count(
+                                    #variant_name,
+                                    1,
+                                    arg_count,
+                                )?;
+                                #get
+                            },
+                        }
+                    },
+                    Fields::Unnamed(fields) => {
+                        let count = fields.unnamed.len();
+                        let fields = (0..count)
+                            .map(|_| quote! { ::wstp::FromLink::from_link(link)? });
+
+                        quote! {
+                            #variant_name if arg_count.is_some() => {
+                                ::wstp::__private::check_arg_count(
+                                    #variant_name,
+                                    #count,
+                                    arg_count,
+                                )?;
+
+                                ::std::result::Result::Ok(
+                                    Self::#variant_ident( #(#fields),* )
+                                )
+                            },
+                        }
+                    },
+                    Fields::Unit => quote! {
+                        #variant_name if arg_count.is_none() => {
+                            ::std::result::Result::Ok(Self::#variant_ident)
+                        },
+                    },
+                }
+            });
+
+            quote! {
+                let (name, arg_count) =
+                    ::wstp::__private::get_name(link, #type_name, #context)?;
+
+                match name.as_str() {
+                    #(#arms)*
+                    _ => ::std::result::Result::Err(
+                        ::wstp::__private::unknown_variant(#type_name, &name)
+                    ),
+                }
+            }
+        },
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span,
+                "FromLink cannot be derived for unions",
+            ))
+        },
+    };
+
+    let generics = add_trait_bounds(generics, parse_quote!(::wstp::FromLink));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::wstp::FromLink for #ident #ty_generics #where_clause {
+            fn from_link(
+                link: &mut ::wstp::Link,
+            ) -> ::std::result::Result<Self, ::wstp::Error> {
+                #body
+            }
+        }
+    })
+}
+
+/// Generate code that reads `<|"name" -> value, ..|>` and constructs `constructor`
+/// from the values.
+fn get_association(
+    type_name: &str,
+    constructor: &TokenStream,
+    names: &[&Ident],
+) -> TokenStream {
+    let keys: Vec<String> = names.iter().map(|name| name.unraw().to_string()).collect();
+    let values: Vec<Ident> = names
+        .iter()
+        .map(|name| format_ident!("field_{}", name))
+        .collect();
+
+    quote! {
+        #( let mut #values = ::std::option::Option::None; )*
+
+        let len = ::wstp::__private::get_association_len(link)?;
+
+        for _ in 0..len {
+            let key = ::wstp::__private::get_rule_key(link)?;
+
+            match key.as_str() {
+                #(
+                    #keys => #values = ::std::option::Option::Some(
+                        ::wstp::FromLink::from_link(link)?
+                    ),
+                )*
+                _ => {
+                    return ::std::result::Result::Err(
+                        ::wstp::__private::unknown_field(#type_name, &key)
+                    )
+                },
+            }
+        }
+
+        ::std::result::Result::Ok(#constructor {
+            #(
+                #names: #values.ok_or_else(|| {
+                    ::wstp::__private::missing_field(#type_name, #keys)
+                })?,
+            )*
+        })
+    }
+}
+
+//======================================
+// Utilities
+//======================================
+
+/// Parse the `#[wstp(context = "...")]` attribute, returning the context that
+/// generated symbols should be in.
+fn parse_context(input: &DeriveInput) -> syn::Result<String> {
+    let mut context = String::from("Global`");
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("wstp") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("context") {
+                let value: LitStr = meta.value()?.parse()?;
+
+                if !value.value().ends_with('`') {
+                    return Err(meta.error("context must end with a '`' character"));
+                }
+
+                context = value.value();
+
+                Ok(())
+            } else {
+                Err(meta.error("unsupported wstp attribute"))
+            }
+        })?;
+    }
+
+    Ok(context)
+}
+
+/// Add a `T: bound` constraint to every type parameter `T` of `generics`.
+fn add_trait_bounds(mut generics: Generics, bound: syn::TypeParamBound) -> Generics {
+    for param in generics.type_params_mut() {
+        param.bounds.push(bound.clone());
+    }
+
+    generics
+}
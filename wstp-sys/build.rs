@@ -81,18 +81,25 @@ fn main() {
         _ => WOLFRAM_VERSION,
     };
 
-    // TODO: Make use of pre-generated bindings useable via a feature flag?
-    //       Using pre-generated bindings seems to currently only have a distinct
-    //       advantage over compile-time-generated bindings when building on
-    //       docs.rs, where the WSTP SDK is not available.
+    // NOTE: wstp-sys does not generate bindings at build time (and does not depend on
+    //       bindgen), so building never requires libclang, which is often
+    //       unavailable e.g. in Windows CI/CD builds. Bindings for a new WSTP version
+    //       or target must be pre-generated and checked in; see docs/Maintenance.md.
     //
-    //       In other situations, using pre-generated bindings doesn't offer the
-    //       advantage of not needing the WSTP SDK available locally, because you
-    //       still need to link against the WSTP static library.
-    //
-    //       NOTE: Pre-generated bindings have the advantage of working when
-    //             libclang is not available (which bindgen requires), which
-    //             happens e.g. in Windows CI/CD builds.
+    //       By default, the bindings for the version of the local Wolfram
+    //       installation are used, if they have been pre-generated. The
+    //       `pregenerated-bindings` feature always selects the bindings for
+    //       `wolfram_version`, so that the bindings used don't depend on the
+    //       build machine.
+    println!("cargo:rerun-if-env-changed=CARGO_FEATURE_PREGENERATED_BINDINGS");
+
+    let wolfram_version = match std::env::var("CARGO_FEATURE_PREGENERATED_BINDINGS") {
+        Ok(_) => wolfram_version,
+        Err(_) => local_bindings_version(app.as_ref(), target_system_id)
+            .filter(|local_version| *local_version >= wolfram_version)
+            .unwrap_or(wolfram_version),
+    };
+
     let bindings_path = use_pregenerated_bindings(wolfram_version, target_system_id);
 
     println!(
@@ -150,6 +157,21 @@ fn use_pregenerated_bindings(
     bindings_path
 }
 
+/// Get the version of the local Wolfram installation, if bindings have been
+/// pre-generated for that version and `target_system_id`.
+fn local_bindings_version(
+    app: Option<&WolframApp>,
+    target_system_id: SystemID,
+) -> Option<WolframVersion> {
+    let local_version = app?.wolfram_version().ok()?;
+
+    if !make_bindings_path(&local_version, target_system_id).is_file() {
+        return None;
+    }
+
+    Some(local_version)
+}
+
 fn make_bindings_path(wolfram_version: &WolframVersion, system_id: SystemID) -> PathBuf {
     let bindings_path = PathBuf::from("generated")
         .join(&wolfram_version.to_string())
@@ -0,0 +1,272 @@
+//! Serialization of expressions to and from an in-memory byte blob.
+//!
+//! WSTP does not provide a way to capture the encoded contents of a link, so a blob
+//! stores the sequence of tokens that make up an expression, as read from a loopback
+//! link using [`Link::get_token()`]. Replaying a blob onto a link writes those same
+//! tokens, without needing to traverse an [`Expr`] again.
+//!
+//! The blob format is an implementation detail of this crate. It may change between
+//! versions of `wstp`, and is not suitable for use as a stable on-disk format.
+
+use std::convert::TryFrom;
+
+use wolfram_expr::Expr;
+
+use crate::{Error, Link, Token, TokenType};
+
+/// Identifies the start of a blob, and the version of the blob format.
+const MAGIC: &[u8] = b"WSTPBLOB\x01";
+
+const TAG_INTEGER: u8 = b'I';
+const TAG_REAL: u8 = b'R';
+const TAG_STRING: u8 = b'S';
+const TAG_SYMBOL: u8 = b'Y';
+const TAG_FUNCTION: u8 = b'F';
+
+/// Token decoded from a blob.
+enum BlobToken<'b> {
+    Integer(i64),
+    Real(f64),
+    String(&'b str),
+    Symbol(&'b str),
+    Function { length: usize },
+}
+
+impl Link {
+    /// Serialize `expr` into a byte blob.
+    ///
+    /// The returned blob can be written to any number of links using
+    /// [`Link::put_bytes()`], which is cheaper than calling [`Link::put_expr()`]
+    /// repeatedly for the same expression.
+    ///
+    /// The blob format is specific to the version of `wstp` that created it, and is not
+    /// a stable on-disk format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    /// use wolfram_expr::{Expr, Symbol};
+    ///
+    /// let expr = Expr::normal(Symbol::new("System`List"), vec![Expr::from(1)]);
+    ///
+    /// let blob: Vec<u8> = Link::to_bytes(&expr).unwrap();
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_bytes(&blob).unwrap();
+    /// link.put_bytes(&blob).unwrap();
+    ///
+    /// assert_eq!(link.get_expr(), Ok(expr.clone()));
+    /// assert_eq!(link.get_expr(), Ok(expr));
+    /// ```
+    pub fn to_bytes(expr: &Expr) -> Result<Vec<u8>, Error> {
+        let mut loopback = Link::new_loopback()?;
+
+        loopback.put_expr(expr)?;
+
+        loopback.take_expr_bytes()
+    }
+
+    /// Read one complete expression from this link, returning it serialized as a byte
+    /// blob.
+    ///
+    /// [`Link::put_bytes()`] is the counterpart of this function: it replays the blob
+    /// onto another link, writing the same expression that was read. The blob can also
+    /// be deserialized using [`expr_from_bytes()`][crate::expr_from_bytes]. This makes
+    /// it possible to buffer expressions received from one link and deliver them later,
+    /// without holding on to either link.
+    ///
+    /// The blob format is specific to the version of `wstp` that created it, and is not
+    /// a stable on-disk format. Like [`Link::get_token()`], integers must fit in an
+    /// [`i64`], and real numbers are read as [`f64`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut a = Link::new_loopback().unwrap();
+    ///
+    /// a.put_function("System`List", 2).unwrap();
+    /// a.put_i64(1).unwrap();
+    /// a.put_str("two").unwrap();
+    ///
+    /// let blob: Vec<u8> = a.take_expr_bytes().unwrap();
+    ///
+    /// let mut b = Link::new_loopback().unwrap();
+    ///
+    /// b.put_bytes(&blob).unwrap();
+    ///
+    /// assert_eq!(b.test_head("System`List"), Ok(2));
+    /// assert_eq!(b.get_i64(), Ok(1));
+    /// assert_eq!(b.get_string().unwrap(), "two");
+    /// ```
+    pub fn take_expr_bytes(&mut self) -> Result<Vec<u8>, Error> {
+        let mut blob: Vec<u8> = MAGIC.to_vec();
+
+        // Number of expressions that still need to be read to complete the expression.
+        let mut remaining: usize = 1;
+
+        while remaining > 0 {
+            remaining -= 1;
+
+            match self.get_token()? {
+                Token::Integer(value) => {
+                    blob.push(TAG_INTEGER);
+                    blob.extend_from_slice(&value.to_le_bytes());
+                },
+                Token::Real(value) => {
+                    blob.push(TAG_REAL);
+                    blob.extend_from_slice(&value.to_le_bytes());
+                },
+                Token::String(string) => {
+                    blob.push(TAG_STRING);
+                    push_str(&mut blob, string.as_str());
+                },
+                Token::Symbol(symbol) => {
+                    blob.push(TAG_SYMBOL);
+                    push_str(&mut blob, symbol.as_str());
+                },
+                Token::Function { length } => {
+                    blob.push(TAG_FUNCTION);
+                    blob.extend_from_slice(&(length as u64).to_le_bytes());
+
+                    // The head, followed by `length` elements.
+                    remaining += length + 1;
+                },
+            }
+        }
+
+        Ok(blob)
+    }
+
+    /// Write an expression previously serialized using [`Link::to_bytes()`] or
+    /// [`Link::take_expr_bytes()`] to this link.
+    ///
+    /// An error is returned if `blob` is not a complete blob created by one of those
+    /// functions. `blob` is validated before anything is written, so no data
+    /// is written to this link if an error is returned.
+    pub fn put_bytes(&mut self, blob: &[u8]) -> Result<(), Error> {
+        // Validate the entire blob before writing any of it to this link.
+        let () = for_each_blob_token(blob, |_| Ok(()))?;
+
+        for_each_blob_token(blob, |token| match token {
+            BlobToken::Integer(value) => self.put_i64(value),
+            BlobToken::Real(value) => self.put_f64(value),
+            BlobToken::String(string) => self.put_str(string),
+            BlobToken::Symbol(symbol) => self.put_symbol(symbol),
+            BlobToken::Function { length } => {
+                self.put_next_type(TokenType::Function)?;
+                self.put_arg_count(length)
+            },
+        })
+    }
+}
+
+/// Deserialize an expression from a blob created by [`Link::to_bytes()`].
+///
+/// An error is returned if `blob` is truncated or is otherwise not a valid blob.
+///
+/// # Example
+///
+/// ```
+/// use wstp::Link;
+/// use wolfram_expr::{Expr, Symbol};
+///
+/// let expr = Expr::normal(Symbol::new("System`List"), vec![Expr::from(1)]);
+///
+/// let blob: Vec<u8> = Link::to_bytes(&expr).unwrap();
+///
+/// assert_eq!(wstp::expr_from_bytes(&blob), Ok(expr));
+///
+/// assert!(wstp::expr_from_bytes(b"garbage").is_err());
+/// ```
+pub fn expr_from_bytes(blob: &[u8]) -> Result<Expr, Error> {
+    let mut loopback = Link::new_loopback()?;
+
+    loopback.put_bytes(blob)?;
+
+    loopback.get_expr()
+}
+
+fn push_str(blob: &mut Vec<u8>, string: &str) {
+    blob.extend_from_slice(&(string.len() as u64).to_le_bytes());
+    blob.extend_from_slice(string.as_bytes());
+}
+
+/// Decode the tokens in `blob`, calling `func` on each one.
+///
+/// Returns an error if `blob` is truncated, malformed, or does not contain exactly one
+/// complete expression.
+fn for_each_blob_token<'b, F>(blob: &'b [u8], mut func: F) -> Result<(), Error>
+where
+    F: FnMut(BlobToken<'b>) -> Result<(), Error>,
+{
+    let invalid = |reason: &str| Error::custom(format!("invalid WSTP blob: {}", reason));
+
+    let mut rest: &'b [u8] = blob
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| invalid("missing header"))?;
+
+    let mut take = |count: usize| -> Result<&'b [u8], Error> {
+        if rest.len() < count {
+            return Err(invalid("unexpected end of data"));
+        }
+
+        let (bytes, remainder) = rest.split_at(count);
+        rest = remainder;
+
+        Ok(bytes)
+    };
+
+    let mut remaining: usize = 1;
+
+    while remaining > 0 {
+        remaining -= 1;
+
+        let tag = take(1)?[0];
+
+        let token = match tag {
+            TAG_INTEGER => BlobToken::Integer(i64::from_le_bytes(array(take(8)?))),
+            TAG_REAL => BlobToken::Real(f64::from_le_bytes(array(take(8)?))),
+            TAG_STRING | TAG_SYMBOL => {
+                let len = usize::try_from(u64::from_le_bytes(array(take(8)?)))
+                    .map_err(|_| invalid("string length overflows usize"))?;
+
+                let string = std::str::from_utf8(take(len)?)
+                    .map_err(|_| invalid("string data is not valid UTF-8"))?;
+
+                if tag == TAG_STRING {
+                    BlobToken::String(string)
+                } else {
+                    BlobToken::Symbol(string)
+                }
+            },
+            TAG_FUNCTION => {
+                let length = usize::try_from(u64::from_le_bytes(array(take(8)?)))
+                    .map_err(|_| invalid("function length overflows usize"))?;
+
+                remaining = remaining
+                    .checked_add(length)
+                    .and_then(|remaining| remaining.checked_add(1))
+                    .ok_or_else(|| invalid("function length is too large"))?;
+
+                BlobToken::Function { length }
+            },
+            _ => return Err(invalid(&format!("unknown token tag: {}", tag))),
+        };
+
+        let () = func(token)?;
+    }
+
+    if !rest.is_empty() {
+        return Err(invalid("unexpected data after end of expression"));
+    }
+
+    Ok(())
+}
+
+fn array(bytes: &[u8]) -> [u8; 8] {
+    <[u8; 8]>::try_from(bytes).expect("expected slice of length 8")
+}
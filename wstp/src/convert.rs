@@ -0,0 +1,364 @@
+//! Traits for writing Rust values to and reading them from a [`Link`].
+//!
+//! [`ToLink`] and [`FromLink`] are implemented for common Rust types, and can be
+//! derived for user-defined structs and enums when the `derive` feature is enabled.
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+
+use wolfram_expr::Expr;
+
+use crate::{Error, Link};
+
+/// A value that can be written to a [`Link`] as an expression.
+///
+/// # Derive
+///
+/// When the `derive` feature is enabled, `ToLink` can be derived for structs and enums
+/// whose fields all implement `ToLink`:
+///
+/// | Rust type                       | Expression written                   |
+/// |---------------------------------|--------------------------------------|
+/// | `struct S { x: T, .. }`         | `<\|"x" -> x, ..\|>`                 |
+/// | `struct S(T, ..)`               | `S[t, ..]`                           |
+/// | `struct S;`                     | `S`                                  |
+/// | `enum E { V { x: T, .. } }`     | `V[<\|"x" -> x, ..\|>]`              |
+/// | `enum E { V(T, ..) }`           | `V[t, ..]`                           |
+/// | `enum E { V }`                  | `V`                                  |
+///
+/// Symbols are created in the ``Global` `` context by default. Use the
+/// `#[wstp(context = "...")]` attribute on the type to choose a different context.
+///
+/// ```
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use wstp::{FromLink, Link, ToLink};
+///
+/// #[derive(ToLink, FromLink, Debug, PartialEq)]
+/// enum Shape {
+///     Circle(f64),
+///     Rect(f64, f64),
+/// }
+///
+/// let mut link = Link::new_loopback().unwrap();
+///
+/// // Writes Global`Rect[1.5, 2.]
+/// Shape::Rect(1.5, 2.0).to_link(&mut link).unwrap();
+///
+/// assert_eq!(Shape::from_link(&mut link), Ok(Shape::Rect(1.5, 2.0)));
+/// # }
+/// ```
+pub trait ToLink {
+    /// Write `self` to `link`.
+    fn to_link(&self, link: &mut Link) -> Result<(), Error>;
+}
+
+/// A value that can be read from a [`Link`].
+///
+/// This is the inverse of [`ToLink`], and can be derived in the same way.
+pub trait FromLink: Sized {
+    /// Read a value of this type from `link`.
+    fn from_link(link: &mut Link) -> Result<Self, Error>;
+}
+
+//======================================
+// Impls
+//======================================
+
+impl<T: ToLink + ?Sized> ToLink for &T {
+    fn to_link(&self, link: &mut Link) -> Result<(), Error> {
+        T::to_link(*self, link)
+    }
+}
+
+impl<T: ToLink + ?Sized> ToLink for Box<T> {
+    fn to_link(&self, link: &mut Link) -> Result<(), Error> {
+        T::to_link(self, link)
+    }
+}
+
+impl<T: FromLink> FromLink for Box<T> {
+    fn from_link(link: &mut Link) -> Result<Self, Error> {
+        T::from_link(link).map(Box::new)
+    }
+}
+
+macro_rules! impl_number {
+    ($($type:ty => $put:ident, $get:ident;)*) => {
+        $(
+            impl ToLink for $type {
+                fn to_link(&self, link: &mut Link) -> Result<(), Error> {
+                    link.$put(*self)
+                }
+            }
+
+            impl FromLink for $type {
+                fn from_link(link: &mut Link) -> Result<Self, Error> {
+                    link.$get()
+                }
+            }
+        )*
+    };
+}
+
+impl_number! {
+    i64 => put_i64, get_i64;
+    i32 => put_i32, get_i32;
+    i16 => put_i16, get_i16;
+    i8 => put_i8, get_i8;
+    u8 => put_u8, get_u8;
+    f64 => put_f64, get_f64;
+    f32 => put_f32, get_f32;
+}
+
+/// Written as the symbol `True` or `False`.
+impl ToLink for bool {
+    fn to_link(&self, link: &mut Link) -> Result<(), Error> {
+        link.put_symbol(if *self { "System`True" } else { "System`False" })
+    }
+}
+
+impl FromLink for bool {
+    fn from_link(link: &mut Link) -> Result<Self, Error> {
+        let symbol = link.get_symbol_ref()?;
+
+        match symbol.as_str() {
+            "System`True" | "True" => Ok(true),
+            "System`False" | "False" => Ok(false),
+            other => Err(Error::custom(format!(
+                "expected True or False, got symbol: {}",
+                other
+            ))),
+        }
+    }
+}
+
+impl ToLink for str {
+    fn to_link(&self, link: &mut Link) -> Result<(), Error> {
+        link.put_str(self)
+    }
+}
+
+impl ToLink for String {
+    fn to_link(&self, link: &mut Link) -> Result<(), Error> {
+        link.put_str(self)
+    }
+}
+
+impl FromLink for String {
+    fn from_link(link: &mut Link) -> Result<Self, Error> {
+        link.get_string()
+    }
+}
+
+impl ToLink for Expr {
+    fn to_link(&self, link: &mut Link) -> Result<(), Error> {
+        link.put_expr(self)
+    }
+}
+
+impl FromLink for Expr {
+    fn from_link(link: &mut Link) -> Result<Self, Error> {
+        link.get_expr()
+    }
+}
+
+/// `None` is written as the symbol `Null`.
+impl<T: ToLink> ToLink for Option<T> {
+    fn to_link(&self, link: &mut Link) -> Result<(), Error> {
+        match self {
+            Some(value) => value.to_link(link),
+            None => link.put_null(),
+        }
+    }
+}
+
+/// `Null` and `Missing[..]` are read as `None`. See [`Link::get_optional()`].
+impl<T: FromLink> FromLink for Option<T> {
+    fn from_link(link: &mut Link) -> Result<Self, Error> {
+        link.get_optional(T::from_link)
+    }
+}
+
+/// Written as a `List[..]` of the elements.
+impl<T: ToLink> ToLink for [T] {
+    fn to_link(&self, link: &mut Link) -> Result<(), Error> {
+        link.put_function("System`List", self.len())?;
+
+        for elem in self {
+            elem.to_link(link)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: ToLink> ToLink for Vec<T> {
+    fn to_link(&self, link: &mut Link) -> Result<(), Error> {
+        self.as_slice().to_link(link)
+    }
+}
+
+impl<T: FromLink> FromLink for Vec<T> {
+    fn from_link(link: &mut Link) -> Result<Self, Error> {
+        let length = link.get_system_function("List")?;
+
+        let mut elements = Vec::new();
+
+        for _ in 0..length {
+            elements.push(T::from_link(link)?);
+        }
+
+        Ok(elements)
+    }
+}
+
+/// Written as an `Association[..]` of `key -> value` rules, in iteration order.
+impl<K: ToLink, V: ToLink, S> ToLink for HashMap<K, V, S> {
+    fn to_link(&self, link: &mut Link) -> Result<(), Error> {
+        link.put_association(
+            self,
+            |link, key| key.to_link(link),
+            |link, value| value.to_link(link),
+        )
+    }
+}
+
+/// Read from an `Association[..]` of `key -> value` rules.
+impl<K, V, S> FromLink for HashMap<K, V, S>
+where
+    K: FromLink + Eq + Hash,
+    V: FromLink,
+    S: BuildHasher + Default,
+{
+    fn from_link(link: &mut Link) -> Result<Self, Error> {
+        link.get_association(K::from_link, V::from_link)
+    }
+}
+
+/// Written as an `Association[..]` of `key -> value` rules, in sorted key order.
+impl<K: ToLink, V: ToLink> ToLink for BTreeMap<K, V> {
+    fn to_link(&self, link: &mut Link) -> Result<(), Error> {
+        link.put_association(
+            self,
+            |link, key| key.to_link(link),
+            |link, value| value.to_link(link),
+        )
+    }
+}
+
+/// Read from an `Association[..]` of `key -> value` rules.
+impl<K: FromLink + Ord, V: FromLink> FromLink for BTreeMap<K, V> {
+    fn from_link(link: &mut Link) -> Result<Self, Error> {
+        link.get_association(K::from_link, V::from_link)
+    }
+}
+
+//======================================
+// Derive support
+//======================================
+
+/// Helpers used by the code generated by `#[derive(ToLink, FromLink)]`.
+///
+/// This module is not part of the public API of this crate.
+#[doc(hidden)]
+pub mod __private {
+    use crate::{Error, Link, TokenType};
+
+    /// Read the symbol or function head that identifies a value of a derived type.
+    ///
+    /// Returns the name of the symbol with `context` removed, and the argument count if
+    /// the incoming expression is a function.
+    pub fn get_name(
+        link: &mut Link,
+        type_name: &str,
+        context: &str,
+    ) -> Result<(String, Option<usize>), Error> {
+        let arg_count: Option<usize> = match link.get_type()? {
+            TokenType::Symbol => None,
+            TokenType::Function => Some(link.get_arg_count()?),
+            _ => {
+                return Err(Error::custom(format!(
+                    "{}: expected symbol or function expression",
+                    type_name
+                )))
+            },
+        };
+
+        let symbol = link.get_symbol_ref()?;
+        let symbol: &str = symbol.as_str();
+
+        let name = match symbol.strip_prefix(context) {
+            Some(name) if !name.contains('`') => name,
+            _ if !symbol.contains('`') => symbol,
+            _ => {
+                return Err(Error::custom(format!(
+                    "{}: expected symbol in context {}, got: {}",
+                    type_name, context, symbol
+                )))
+            },
+        };
+
+        Ok((name.to_owned(), arg_count))
+    }
+
+    /// Check that a function expression representing `name` has `expected` arguments.
+    pub fn check_arg_count(
+        name: &str,
+        expected: usize,
+        actual: Option<usize>,
+    ) -> Result<(), Error> {
+        match actual {
+            Some(actual) if actual == expected => Ok(()),
+            Some(actual) => Err(Error::custom(format!(
+                "{}: expected {} arguments, got {}",
+                name, expected, actual
+            ))),
+            None => Err(Error::custom(format!(
+                "{}: expected function expression, got symbol",
+                name
+            ))),
+        }
+    }
+
+    /// Read the `Association[..]` head, returning the number of rules.
+    pub fn get_association_len(link: &mut Link) -> Result<usize, Error> {
+        link.get_system_function("Association")
+    }
+
+    /// Read the `Rule[..]` head and string key of an association rule, leaving the
+    /// value to be read.
+    pub fn get_rule_key(link: &mut Link) -> Result<String, Error> {
+        let argc = link.get_system_function("Rule")?;
+
+        if argc != 2 {
+            return Err(Error::custom(format!(
+                "expected Rule with 2 arguments, got {}",
+                argc
+            )));
+        }
+
+        link.get_string()
+    }
+
+    /// Write an association rule with a string key, leaving the value to be written.
+    pub fn put_rule_key(link: &mut Link, key: &str) -> Result<(), Error> {
+        link.put_function("System`Rule", 2)?;
+        link.put_str(key)
+    }
+
+    /// Error for a symbol that does not name a variant of `type_name`.
+    pub fn unknown_variant(type_name: &str, name: &str) -> Error {
+        Error::custom(format!("{}: unknown variant: {}", type_name, name))
+    }
+
+    /// Error for an association key that does not name a field of `type_name`.
+    pub fn unknown_field(type_name: &str, field: &str) -> Error {
+        Error::custom(format!("{}: unknown field: {}", type_name, field))
+    }
+
+    /// Error for a field of `type_name` that was not present in an association.
+    pub fn missing_field(type_name: &str, field: &str) -> Error {
+        Error::custom(format!("{}: missing field: {}", type_name, field))
+    }
+}
@@ -0,0 +1,207 @@
+//! Conversions between [`DateObject`][DateObject] expressions and [`chrono`] types.
+//!
+//! This module is only available when the `chrono` feature is enabled.
+//!
+//! [DateObject]: https://reference.wolfram.com/language/ref/DateObject.html
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, Timelike, Utc};
+use wolfram_expr::{Expr, ExprKind, Normal, Symbol};
+
+use crate::{Error, Link};
+
+impl Link {
+    /// Put a [`DateObject`][DateObject] representing `datetime` onto this link.
+    ///
+    /// The expression written has the form:
+    ///
+    /// ```text
+    /// DateObject[{year, month, day, hour, minute, second}, "Instant", "Gregorian", 0.]
+    /// ```
+    ///
+    /// where `second` is a real number that includes any fractional part of the
+    /// second, and the time zone is always UTC (`0.`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// let datetime = Utc.with_ymd_and_hms(2022, 3, 14, 15, 9, 26).unwrap();
+    ///
+    /// link.put_datetime(datetime).unwrap();
+    ///
+    /// assert_eq!(link.get_datetime(), Ok(datetime));
+    /// ```
+    ///
+    /// [DateObject]: https://reference.wolfram.com/language/ref/DateObject.html
+    pub fn put_datetime(&mut self, datetime: DateTime<Utc>) -> Result<(), Error> {
+        let seconds = f64::from(datetime.second())
+            + f64::from(datetime.nanosecond()) / 1_000_000_000.0;
+
+        self.put_function("System`DateObject", 4)?;
+
+        self.put_function("System`List", 6)?;
+        self.put_i64(i64::from(datetime.year()))?;
+        self.put_i64(i64::from(datetime.month()))?;
+        self.put_i64(i64::from(datetime.day()))?;
+        self.put_i64(i64::from(datetime.hour()))?;
+        self.put_i64(i64::from(datetime.minute()))?;
+        self.put_f64(seconds)?;
+
+        self.put_str("Instant")?;
+        self.put_str("Gregorian")?;
+        self.put_f64(0.0)?;
+
+        Ok(())
+    }
+
+    /// Get a [`DateObject`][DateObject] from this link as a UTC [`DateTime`].
+    ///
+    /// The `DateObject` must have one of the forms:
+    ///
+    /// * `DateObject[{y, m, d, h, m, s}]`
+    /// * `DateObject[{y, m, d, h, m, s}, granularity, calendar, timezone]`
+    ///
+    /// Trailing elements of the date list may be omitted, in which case they default to
+    /// the start of the month, day, hour, or minute. The seconds may be an integer or a
+    /// real number. `timezone` must be a number giving the offset from UTC in hours.
+    /// If no time zone is present, the date is assumed to be in UTC.
+    ///
+    /// Symbols without a context, like those in a `DateObject` sent by the Kernel, are
+    /// read as `` System` `` symbols.
+    ///
+    /// [DateObject]: https://reference.wolfram.com/language/ref/DateObject.html
+    pub fn get_datetime(&mut self) -> Result<DateTime<Utc>, Error> {
+        let expr = self.get_system_expr()?;
+
+        let normal: &Normal = match expr.kind() {
+            ExprKind::Normal(normal)
+                if normal.has_head(&Symbol::new("System`DateObject")) =>
+            {
+                normal
+            },
+            _ => {
+                return Err(Error::custom(format!(
+                    "get_datetime: expected DateObject expression, got: {}",
+                    expr
+                )))
+            },
+        };
+
+        let (date_list, timezone): (&Expr, Option<&Expr>) = match normal.elements() {
+            [date_list] => (date_list, None),
+            [date_list, _granularity, _calendar, timezone] => (date_list, Some(timezone)),
+            _ => {
+                return Err(Error::custom(format!(
+                    "get_datetime: unsupported DateObject form: {}",
+                    expr
+                )))
+            },
+        };
+
+        let naive = naive_datetime_from_date_list(date_list)?;
+
+        let offset_seconds: i32 = match timezone {
+            Some(timezone) => timezone_offset_seconds(timezone)?,
+            None => 0,
+        };
+
+        let offset = FixedOffset::east_opt(offset_seconds).ok_or_else(|| {
+            Error::custom(format!(
+                "get_datetime: time zone offset out of range: {}",
+                expr
+            ))
+        })?;
+
+        let datetime: DateTime<FixedOffset> =
+            naive.and_local_timezone(offset).single().ok_or_else(|| {
+                Error::custom(format!("get_datetime: invalid date: {}", expr))
+            })?;
+
+        Ok(datetime.with_timezone(&Utc))
+    }
+}
+
+fn naive_datetime_from_date_list(date_list: &Expr) -> Result<NaiveDateTime, Error> {
+    let invalid = || {
+        Error::custom(format!(
+            "get_datetime: invalid DateObject date list: {}",
+            date_list
+        ))
+    };
+
+    let elements: &[Expr] = match date_list.kind() {
+        ExprKind::Normal(list) if list.has_head(&Symbol::new("System`List")) => {
+            list.elements()
+        },
+        _ => return Err(invalid()),
+    };
+
+    if elements.is_empty() || elements.len() > 6 {
+        return Err(invalid());
+    }
+
+    // Year, month, day, hour, minute. Missing components default to the start of the
+    // enclosing unit.
+    let mut fields: [i64; 5] = [0, 1, 1, 0, 0];
+
+    for (field, elem) in fields.iter_mut().zip(elements.iter().take(5)) {
+        *field = match elem.kind() {
+            ExprKind::Integer(int) => *int,
+            _ => return Err(invalid()),
+        };
+    }
+
+    let seconds: f64 = match elements.get(5).map(Expr::kind) {
+        None => 0.0,
+        Some(ExprKind::Integer(int)) => *int as f64,
+        Some(ExprKind::Real(real)) => **real,
+        Some(_) => return Err(invalid()),
+    };
+
+    if !(0.0..60.0).contains(&seconds) {
+        return Err(invalid());
+    }
+
+    let [year, month, day, hour, minute] = fields;
+
+    let whole_seconds = seconds.trunc();
+    let nanoseconds = ((seconds - whole_seconds) * 1_000_000_000.0).round();
+
+    let date = NaiveDate::from_ymd_opt(
+        i32::try_from(year).map_err(|_| invalid())?,
+        u32::try_from(month).map_err(|_| invalid())?,
+        u32::try_from(day).map_err(|_| invalid())?,
+    )
+    .ok_or_else(invalid)?;
+
+    date.and_hms_nano_opt(
+        u32::try_from(hour).map_err(|_| invalid())?,
+        u32::try_from(minute).map_err(|_| invalid())?,
+        whole_seconds as u32,
+        // Rounding can produce exactly one second worth of nanoseconds, which
+        // `and_hms_nano_opt()` would interpret as a leap second.
+        (nanoseconds as u32).min(999_999_999),
+    )
+    .ok_or_else(invalid)
+}
+
+/// Convert a `DateObject` time zone, given as an offset from UTC in hours, into an
+/// offset in seconds.
+fn timezone_offset_seconds(timezone: &Expr) -> Result<i32, Error> {
+    let hours: f64 = match timezone.kind() {
+        ExprKind::Integer(int) => *int as f64,
+        ExprKind::Real(real) => **real,
+        _ => {
+            return Err(Error::custom(format!(
+                "get_datetime: unsupported DateObject time zone: {}",
+                timezone
+            )))
+        },
+    };
+
+    Ok((hours * 3600.0).round() as i32)
+}
@@ -24,6 +24,8 @@
 //!   * All [`Link`][crate::Link]'s MUST be closed before the `WstpEnv` they are
 //!     associated with is deinitialized (essentially a restatement of the first condition).
 
+use std::fmt;
+use std::os::raw::{c_int, c_ulong};
 use std::sync::Mutex;
 
 use crate::{sys, Error};
@@ -31,12 +33,15 @@ use crate::{sys, Error};
 /// The standard WSTP environment object.
 ///
 /// *WSTP C API Documentation:* [`stdenv`](https://reference.wolfram.com/language/ref/c/stdenv.html)
-static STDENV: Mutex<StdEnvState> = Mutex::new(StdEnvState::Uninitialized);
+static STDENV: Mutex<StdEnvState> = Mutex::new(StdEnvState::Uninitialized(Vec::new()));
 
 enum StdEnvState {
     /// No links have been created yet, so the lazily initialized STDENV is
     /// empty.
-    Uninitialized,
+    ///
+    /// Holds the signals that WSTP should not install handlers for when STDENV is
+    /// initialized, set using [`disable_signal_handlers()`].
+    Uninitialized(Vec<c_int>),
     Initialized(WstpEnv),
     /// The WSTP library was shutdown so the STDENV was deinitialized and cannot
     /// be re-initialized.
@@ -71,12 +76,13 @@ pub(crate) fn with_raw_stdenv<T, F: FnOnce(sys::WSENV) -> T>(
         Error::custom(format!("Unable to acquire lock on STDENV: {}", err))
     })?;
 
-    if let StdEnvState::Uninitialized = *guard {
-        *guard = StdEnvState::Initialized(WstpEnv::initialize().unwrap())
+    if let StdEnvState::Uninitialized(unhandled_signals) = &*guard {
+        let stdenv = WstpEnv::initialize(unhandled_signals).unwrap();
+        *guard = StdEnvState::Initialized(stdenv)
     }
 
     let raw_env = match &*guard {
-        StdEnvState::Uninitialized => unreachable!(),
+        StdEnvState::Uninitialized(_) => unreachable!(),
         StdEnvState::Initialized(stdenv) => stdenv.raw_env,
         StdEnvState::Shutdown => {
             return Err(Error::custom(
@@ -94,6 +100,87 @@ pub(crate) fn with_raw_stdenv<T, F: FnOnce(sys::WSENV) -> T>(
     Ok(result)
 }
 
+/// Get the raw [`WSENV`][sys::WSENV] that is used by this library to create links.
+///
+/// This is an escape hatch for calling [`sys`] functions that require a `WSENV`
+/// argument, analogous to [`Link::raw_link()`][crate::Link::raw_link]. The global
+/// environment is initialized if it has not been already.
+///
+/// An error is returned if the global environment has been deinitialized by
+/// [`shutdown()`].
+///
+/// # Safety
+///
+/// The returned `WSENV` is only valid until [`shutdown()`] is called, and must not be
+/// used afterwards. The `WSENV` must not be deinitialized by the caller.
+///
+/// This library synchronizes its own use of the global environment, but that
+/// synchronization does not extend to the returned pointer. The caller must ensure that
+/// any `WSENV` function that modifies the environment is not called while other threads
+/// may be creating links.
+///
+/// *WSTP C API Documentation:* [`WSENV`](https://reference.wolfram.com/language/ref/c/WSENV.html)
+pub unsafe fn raw_stdenv() -> Result<sys::WSENV, Error> {
+    with_raw_stdenv(|raw_env| raw_env)
+}
+
+/// Prevent WSTP from installing handlers for `signals` when it is initialized.
+///
+/// By default, the WSTP library installs its own handlers for some signals when the
+/// global WSTP environment is initialized. Applications that manage their own signal
+/// handling, e.g. for graceful shutdown on `SIGINT` or `SIGTERM`, can use this function
+/// to ensure that their handlers are not replaced.
+///
+/// This function must be called before the first [`Link`][crate::Link] is created (or
+/// any other function that initializes the global WSTP environment is called). An
+/// error is returned if the environment has already been initialized.
+///
+/// Calling this function more than once adds to the set of signals that will not be
+/// handled.
+///
+/// # Signal handling responsibilities
+///
+/// WSTP uses its signal handlers to keep link operations working correctly. For
+/// example, on Unix platforms WSTP handles `SIGPIPE` so that writing to a link whose
+/// peer has disconnected is reported as a link error, instead of terminating the
+/// process. If WSTP is prevented from handling a signal, the application becomes
+/// responsible for handling (or ignoring) it in a way that does not disrupt WSTP.
+///
+/// # Example
+///
+/// ```no_run
+/// // Numeric values of SIGINT and SIGTERM on Linux and macOS.
+/// const SIGINT: i32 = 2;
+/// const SIGTERM: i32 = 15;
+///
+/// wstp::disable_signal_handlers(&[SIGINT, SIGTERM]).unwrap();
+///
+/// // ... install application signal handlers, then create links ...
+/// ```
+///
+/// *WSTP C API Documentation:* [`WSDoNotHandleSignalParameter()`](https://reference.wolfram.com/language/ref/c/WSDoNotHandleSignalParameter.html)
+pub fn disable_signal_handlers(signals: &[c_int]) -> Result<(), Error> {
+    let mut guard = STDENV.lock().map_err(|err| {
+        Error::custom(format!("Unable to acquire lock on STDENV: {}", err))
+    })?;
+
+    match &mut *guard {
+        StdEnvState::Uninitialized(unhandled_signals) => {
+            unhandled_signals.extend_from_slice(signals)
+        },
+        StdEnvState::Initialized(_) | StdEnvState::Shutdown => {
+            return Err(Error::custom(
+                "disable_signal_handlers() must be called before the WSTP environment \
+                 is initialized"
+                    .to_owned(),
+            ))
+        },
+    }
+
+    drop(guard);
+
+    Ok(())
+}
 
 /// Deinitialize the [`WSENV`] static maintained by this library.
 ///
@@ -124,7 +211,7 @@ pub unsafe fn shutdown() -> Result<bool, Error> {
     let state = std::mem::replace(&mut *guard, StdEnvState::Shutdown);
 
     let was_initialized = match state {
-        StdEnvState::Uninitialized => false,
+        StdEnvState::Uninitialized(_) => false,
         StdEnvState::Initialized(stdenv) => {
             stdenv.deinitialize();
             true
@@ -137,16 +224,115 @@ pub unsafe fn shutdown() -> Result<bool, Error> {
     Ok(was_initialized)
 }
 
+/// Version numbers of the WSTP library linked into the current program.
+///
+/// Use [`library_version()`][crate::library_version] to query the version of the WSTP
+/// library in use at runtime.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LibraryVersion {
+    /// WSTP interface number.
+    ///
+    /// The interface number is incremented when changes are made to the WSTP API that
+    /// are not backwards compatible.
+    pub interface: i32,
+    /// WSTP revision number.
+    ///
+    /// The revision number is incremented when new functionality is added to the WSTP
+    /// API in a backwards compatible manner.
+    pub revision: i32,
+    /// WSTP build number.
+    pub build: i32,
+}
+
+/// Get the version of the WSTP library linked into the current program.
+///
+/// # Example
+///
+/// ```
+/// let version = wstp::library_version().unwrap();
+///
+/// println!("WSTP library version: {}", version);
+///
+/// assert_eq!(version.interface, wstp::sys::WSINTERFACE);
+/// ```
+///
+/// *WSTP C API Documentation:* [`WSVersionNumbers()`](https://reference.wolfram.com/language/ref/c/WSVersionNumbers.html)
+pub fn library_version() -> Result<LibraryVersion, Error> {
+    let mut interface: std::os::raw::c_int = 0;
+    let mut revision: std::os::raw::c_int = 0;
+    let mut build: std::os::raw::c_int = 0;
+
+    let () = with_raw_stdenv(|raw_stdenv| unsafe {
+        sys::WSVersionNumbers(raw_stdenv, &mut interface, &mut revision, &mut build)
+    })?;
+
+    Ok(LibraryVersion {
+        interface,
+        revision,
+        build,
+    })
+}
+
+impl fmt::Display for LibraryVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let LibraryVersion {
+            interface,
+            revision,
+            build,
+        } = *self;
+
+        write!(f, "{}.{}.{}", interface, revision, build)
+    }
+}
+
 impl WstpEnv {
     /// Private.
     ///
     /// NOTE: This function should remain private. See note on [`crate::env`].
     ///
+    /// WSTP will not install handlers for any of the signals in `unhandled_signals`.
+    ///
     /// *WSTP C API Documentation:* [`WSInitialize()`](https://reference.wolfram.com/language/ref/c/WSInitialize.html)
-    pub(crate) fn initialize() -> Result<Self, Error> {
+    pub(crate) fn initialize(unhandled_signals: &[c_int]) -> Result<Self, Error> {
+        // Only construct a parameters object if there are non-default parameters.
+        let params: sys::WSEnvironmentParameter = if unhandled_signals.is_empty() {
+            std::ptr::null_mut()
+        } else {
+            let params = unsafe {
+                sys::WSNewParameters(
+                    sys::WSREVISION as c_ulong,
+                    sys::WSAPIREVISION as c_ulong,
+                )
+            };
+
+            if params.is_null() {
+                return Err(Error::custom("WSNewParameters() failed".to_owned()));
+            }
+
+            for &signal in unhandled_signals {
+                let code = unsafe { sys::WSDoNotHandleSignalParameter(params, signal) };
+
+                if code != sys::WSEOK as std::os::raw::c_long {
+                    unsafe { sys::WSReleaseParameters(params) };
+
+                    return Err(Error::custom(format!(
+                        "WSDoNotHandleSignalParameter() failed for signal {}: error \
+                         code {}",
+                        signal, code
+                    )));
+                }
+            }
+
+            params
+        };
+
         // TODO: Is this thread-safe?
         //       Is it safe to call WSInitialize() multiple times in the same process?
-        let raw_env: sys::WSENV = unsafe { sys::WSInitialize(std::ptr::null_mut()) };
+        let raw_env: sys::WSENV = unsafe { sys::WSInitialize(params) };
+
+        if !params.is_null() {
+            unsafe { sys::WSReleaseParameters(params) };
+        }
 
         if raw_env.is_null() {
             return Err(Error::custom(
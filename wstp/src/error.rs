@@ -11,6 +11,12 @@ use std::{
 pub struct Error {
     pub(crate) code: Option<i32>,
     pub(crate) message: String,
+    /// Whether this error was caused by reading past the end of the data available on a
+    /// loopback link. See [`Error::is_end_of_data()`].
+    pub(crate) end_of_data: bool,
+    /// Whether this error was caused by an operation timing out. See
+    /// [`Error::is_timeout()`].
+    pub(crate) timed_out: bool,
 }
 
 impl Error {
@@ -22,13 +28,158 @@ impl Error {
         self.code
     }
 
+    /// Prepend `context` to the message of this error, preserving its error code.
+    ///
+    /// This can be used to describe the operation that failed when propagating an
+    /// error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_str("not an integer").unwrap();
+    ///
+    /// let err = link.get_i64().unwrap_err();
+    /// let code = err.code();
+    ///
+    /// let err = err.with_context("reading the request id");
+    ///
+    /// assert_eq!(err.code(), code);
+    /// assert!(err.to_string().contains("reading the request id: "));
+    /// ```
+    pub fn with_context<C: Display>(self, context: C) -> Error {
+        let Error {
+            code,
+            message,
+            end_of_data,
+            timed_out,
+        } = self;
+
+        Error {
+            code,
+            message: format!("{}: {}", context, message),
+            end_of_data,
+            timed_out,
+        }
+    }
+
+    /// Returns `true` if this error was caused by attempting to read past the end of
+    /// the data available on a link.
+    ///
+    /// This can be used to stop reading from a loopback link once it is empty, without
+    /// comparing against raw WSTP error codes.
+    ///
+    /// WSTP reports this condition using the [`WSEABORT`][crate::sys::WSEABORT] error
+    /// code, which is also used when an operation on a link to another process is
+    /// aborted. To distinguish the two, whether the error marks the end of the data is
+    /// determined when the error is read from the link: it must be a `WSEABORT` error
+    /// returned by a loopback link with no data left to read. Aborts of links to
+    /// other processes are never reported as the end of the data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_i64(1).unwrap();
+    ///
+    /// assert_eq!(link.get_i64(), Ok(1));
+    /// assert!(link.raw_get_next().unwrap_err().is_end_of_data());
+    /// ```
+    pub fn is_end_of_data(&self) -> bool {
+        self.end_of_data
+    }
+
+    /// Returns `true` if this error was caused by an operation not completing before its
+    /// timeout elapsed.
+    ///
+    /// This is currently only the case for errors returned by
+    /// [`Link::activate_timeout()`][crate::Link::activate_timeout].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use wstp::{Link, Protocol};
+    ///
+    /// // Nothing ever connects to this link.
+    /// let mut listener = Link::listen(Protocol::IntraProcess, "").unwrap();
+    ///
+    /// let err = listener.activate_timeout(Duration::from_millis(20)).unwrap_err();
+    ///
+    /// assert!(err.is_timeout());
+    /// ```
+    pub fn is_timeout(&self) -> bool {
+        self.timed_out
+    }
+
+    /// Returns `true` if this error was caused by a condition that may be temporary,
+    /// such that retrying the operation that failed could succeed.
+    ///
+    /// This is intended for use when implementing reconnection logic. For example, if
+    /// [`Link::activate()`][crate::Link::activate] fails because the peer closed the
+    /// connection or could not be reached, a new link can be created and activated
+    /// again. Errors like a protocol mismatch or an invalid link name are not retryable,
+    /// because repeating the same operation will fail in the same way.
+    ///
+    /// The following WSTP error codes are considered retryable:
+    ///
+    /// * [`WSEDEAD`][crate::sys::WSEDEAD]
+    /// * [`WSECLOSED`][crate::sys::WSECLOSED]
+    /// * [`WSECONNECT`][crate::sys::WSECONNECT]
+    /// * [`WSEACCEPT`][crate::sys::WSEACCEPT]
+    /// * [`WSELAUNCHAGAIN`][crate::sys::WSELAUNCHAGAIN]
+    /// * [`WSERESOURCE`][crate::sys::WSERESOURCE]
+    /// * [`WSESERVICENOTAVAILABLE`][crate::sys::WSESERVICENOTAVAILABLE]
+    ///
+    /// Errors that do not have a WSTP error code, such as the error returned when
+    /// [`Link::activate_timeout()`][crate::Link::activate_timeout] times out, are not
+    /// considered retryable. Use [`Error::is_timeout()`] to detect timeouts.
+    pub fn is_retryable(&self) -> bool {
+        use crate::sys::{
+            WSEACCEPT, WSECLOSED, WSECONNECT, WSEDEAD, WSELAUNCHAGAIN, WSERESOURCE,
+            WSESERVICENOTAVAILABLE,
+        };
+
+        matches!(
+            self.code,
+            Some(
+                WSEDEAD
+                    | WSECLOSED
+                    | WSECONNECT
+                    | WSEACCEPT
+                    | WSELAUNCHAGAIN
+                    | WSERESOURCE
+                    | WSESERVICENOTAVAILABLE
+            )
+        )
+    }
+
     pub(crate) fn custom(message: String) -> Self {
         Error {
             code: None,
             message,
+            end_of_data: false,
+            timed_out: false,
+        }
+    }
+
+    pub(crate) fn timeout(message: String) -> Self {
+        Error {
+            timed_out: true,
+            ..Error::custom(message)
         }
     }
 
+    pub(crate) fn symbol_has_no_context(symbol_str: &str) -> Self {
+        Error::custom(format!("symbol name '{}' has no context", symbol_str))
+    }
+
     pub(crate) fn from_code(code: i32) -> Self {
         // Lookup the error string describing this error code.
         let builtin_message: Result<Option<String>, Error> =
@@ -57,13 +208,20 @@ impl Error {
         Error {
             code: Some(code),
             message,
+            end_of_data: false,
+            timed_out: false,
         }
     }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let Error { code, message } = self;
+        let Error {
+            code,
+            message,
+            end_of_data: _,
+            timed_out: _,
+        } = self;
 
         if let Some(code) = code {
             write!(f, "WSTP error (code {}): {}", code, message)
@@ -81,3 +239,41 @@ impl Debug for Error {
 }
 
 impl std::error::Error for Error {}
+
+//======================================
+// std::io::Error conversions
+//======================================
+
+/// Convert a WSTP error into an [`io::Error`][std::io::Error].
+///
+/// Error codes that describe a failed or lost connection are mapped to the closest
+/// [`io::ErrorKind`][std::io::ErrorKind] (e.g. [`WSECLOSED`][crate::sys::WSECLOSED] to
+/// [`BrokenPipe`][std::io::ErrorKind::BrokenPipe]). All other errors are mapped to
+/// [`ErrorKind::Other`][std::io::ErrorKind::Other]. The original [`Error`] is
+/// preserved as the inner error, and can be retrieved using
+/// [`io::Error::into_inner()`][std::io::Error::into_inner].
+impl From<Error> for std::io::Error {
+    fn from(err: Error) -> std::io::Error {
+        use crate::sys;
+        use std::io::ErrorKind;
+
+        let kind = match err.code {
+            Some(sys::WSEDEAD) => ErrorKind::ConnectionReset,
+            Some(sys::WSECLOSED) => ErrorKind::BrokenPipe,
+            Some(sys::WSECONNECT) => ErrorKind::ConnectionRefused,
+            Some(sys::WSENAMETAKEN) => ErrorKind::AddrInUse,
+            Some(sys::WSEMEM) => ErrorKind::OutOfMemory,
+            Some(sys::WSEBADPARAM | sys::WSEBADARGUMENT) => ErrorKind::InvalidInput,
+            _ => ErrorKind::Other,
+        };
+
+        std::io::Error::new(kind, err)
+    }
+}
+
+/// Convert an [`io::Error`][std::io::Error] into a WSTP error with no error code.
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Error {
+        Error::custom(format!("I/O error: {}", err))
+    }
+}
@@ -1,6 +1,17 @@
 use std::ffi::{CStr, CString};
 use std::iter::FromIterator;
-use std::{convert::TryFrom, fmt, os::raw::c_char};
+use std::str::FromStr;
+use std::{
+    convert::TryFrom,
+    fmt,
+    ops::Index,
+    os::raw::{c_char, c_int},
+};
+
+use wolfram_expr::{
+    symbol::{Context, SymbolName},
+    Expr, ExprKind, Symbol,
+};
 
 use crate::{
     sys::{
@@ -10,7 +21,7 @@ use crate::{
         WSReleaseUTF32String, WSReleaseUTF32Symbol, WSReleaseUTF8String,
         WSReleaseUTF8Symbol,
     },
-    Error, Link, Utf16Str, Utf32Str, Utf8Str,
+    link_state, Error, Link, Utf16Str, Utf32Str, Utf8Str,
 };
 
 /// Basic unit of expression data read from a [`Link`].
@@ -53,6 +64,69 @@ pub enum TokenType {
     Function,
 }
 
+/// The type of the elements of a numeric array available to read from a [`Link`].
+///
+/// See [`Link::peek_array_type()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ArrayElementType {
+    /// The array elements are integers.
+    ///
+    /// The array can be read using [`Link::get_i64_array()`] or, if the elements fit in
+    /// a smaller type, the other integer array getters.
+    Integer,
+    /// The array elements are real numbers.
+    ///
+    /// The array can be read using [`Link::get_f64_array()`] or
+    /// [`Link::get_f32_array()`].
+    Real,
+}
+
+/// The order in which the elements of a multidimensional array are stored in a flat
+/// buffer.
+///
+/// WSTP always transmits arrays in [`RowMajor`][ArrayOrder::RowMajor] order. Functions
+/// like [`Link::put_i64_array_with_order()`] and [`Link::get_i64_array_with_order()`]
+/// convert to and from [`ColumnMajor`][ArrayOrder::ColumnMajor] order while copying
+/// the array data, for use with numeric libraries that store arrays in that order.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum ArrayOrder {
+    /// The last index varies fastest, as in C and Rust. This is the order used by WSTP.
+    #[default]
+    RowMajor,
+    /// The first index varies fastest, as in Fortran and LAPACK.
+    ColumnMajor,
+}
+
+/// A [`NumericArray`](https://reference.wolfram.com/language/ref/NumericArray.html)
+/// read from a [`Link`], including its element type.
+///
+/// See [`Link::get_numeric_array()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumericArray {
+    data: NumericArrayData,
+    dimensions: Vec<usize>,
+}
+
+/// The elements of a [`NumericArray`], stored as a flat buffer in row-major order.
+///
+/// Each variant corresponds to one of the element types of a Wolfram Language
+/// `NumericArray`.
+#[allow(missing_docs)]
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumericArrayData {
+    Integer8(Vec<i8>),
+    Integer16(Vec<i16>),
+    Integer32(Vec<i32>),
+    Integer64(Vec<i64>),
+    UnsignedInteger8(Vec<u8>),
+    UnsignedInteger16(Vec<u16>),
+    UnsignedInteger32(Vec<u32>),
+    UnsignedInteger64(Vec<u64>),
+    Real32(Vec<f32>),
+    Real64(Vec<f64>),
+}
+
 /// String borrowed from a [`Link`].
 ///
 /// `LinkStr` is returned from:
@@ -152,7 +226,7 @@ impl Link {
     /// assert!(matches!(link.get_token().unwrap(), Token::String(s) if s.as_str() == "second"));
     /// assert!(matches!(link.get_token().unwrap(), Token::Symbol(s) if s.as_str() == "Global`foo"));
     /// ```
-    pub fn get_token(&mut self) -> Result<Token, Error> {
+    pub fn get_token(&mut self) -> Result<Token<'_>, Error> {
         let token = match self.get_type()? {
             TokenType::Integer => Token::Integer(self.get_i64()?),
             TokenType::Real => Token::Real(self.get_f64()?),
@@ -215,6 +289,8 @@ impl Link {
 
         let num_bytes = usize::try_from(num_bytes).unwrap();
 
+        link_state::element_read(self.raw_link);
+
         Ok(LinkStr {
             link: self,
             ptr: c_string,
@@ -250,6 +326,8 @@ impl Link {
 
         let num_bytes = usize::try_from(num_bytes).unwrap();
 
+        link_state::element_read(self.raw_link);
+
         Ok(LinkStr {
             link: self,
             ptr: c_string,
@@ -258,6 +336,147 @@ impl Link {
         })
     }
 
+    /// Read a symbol from this link.
+    ///
+    /// An error is returned if the symbol read is not an absolute symbol name (i.e. does
+    /// not include a context). Use [`Link::get_symbol_in_context()`] to read a symbol
+    /// that may not have a context.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wolfram_expr::Symbol;
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_symbol("Global`x").unwrap();
+    ///
+    /// assert_eq!(link.get_symbol(), Ok(Symbol::new("Global`x")));
+    /// ```
+    pub fn get_symbol(&mut self) -> Result<Symbol, Error> {
+        let symbol_str = self.get_symbol_ref()?;
+
+        Symbol::try_new(symbol_str.as_str())
+            .ok_or_else(|| Error::symbol_has_no_context(symbol_str.as_str()))
+    }
+
+    /// Read a symbol from this link, resolving it in `context` if it does not include a
+    /// context.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wolfram_expr::{symbol::Context, Symbol};
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_symbol("List").unwrap();
+    /// link.put_symbol("Global`x").unwrap();
+    ///
+    /// assert_eq!(
+    ///     link.get_symbol_in_context(&Context::system()),
+    ///     Ok(Symbol::new("System`List"))
+    /// );
+    /// assert_eq!(
+    ///     link.get_symbol_in_context(&Context::system()),
+    ///     Ok(Symbol::new("Global`x"))
+    /// );
+    /// ```
+    pub fn get_symbol_in_context(&mut self, context: &Context) -> Result<Symbol, Error> {
+        let symbol_str = self.get_symbol_ref()?;
+        let symbol_str: &str = symbol_str.as_str();
+
+        if let Some(symbol) = Symbol::try_new(symbol_str) {
+            return Ok(symbol);
+        }
+
+        match SymbolName::try_new(symbol_str) {
+            Some(name) => Ok(Symbol::new(&format!(
+                "{}{}",
+                context.as_str(),
+                name.as_str()
+            ))),
+            None => Err(Error::custom(format!(
+                "invalid symbol name: '{}'",
+                symbol_str
+            ))),
+        }
+    }
+
+    /// Read a symbol in `context` from this link, and parse its name into a value of
+    /// type `E` using [`FromStr`].
+    ///
+    /// The symbol is resolved in the same way as [`Link::get_symbol_in_context()`]. An
+    /// error is returned if the symbol is not in `context`, or if its name could not be
+    /// parsed as an `E`.
+    ///
+    /// This is useful for protocols that use a fixed set of symbols as an enumeration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use wolfram_expr::symbol::Context;
+    /// use wstp::Link;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// enum Color {
+    ///     Red,
+    ///     Green,
+    ///     Blue,
+    /// }
+    ///
+    /// impl FromStr for Color {
+    ///     type Err = String;
+    ///
+    ///     fn from_str(name: &str) -> Result<Color, String> {
+    ///         match name {
+    ///             "Red" => Ok(Color::Red),
+    ///             "Green" => Ok(Color::Green),
+    ///             "Blue" => Ok(Color::Blue),
+    ///             _ => Err(format!("unknown color: {}", name)),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_symbol("Global`Green").unwrap();
+    /// link.put_symbol("Global`Purple").unwrap();
+    ///
+    /// let context = Context::global();
+    ///
+    /// assert_eq!(link.get_symbol_enum::<Color>(&context), Ok(Color::Green));
+    /// assert!(link.get_symbol_enum::<Color>(&context).is_err());
+    /// ```
+    pub fn get_symbol_enum<E>(&mut self, context: &Context) -> Result<E, Error>
+    where
+        E: FromStr,
+        E::Err: fmt::Display,
+    {
+        let symbol: Symbol = self.get_symbol_in_context(context)?;
+
+        if symbol.context().as_str() != context.as_str() {
+            return Err(Error::custom(format!(
+                "get_symbol_enum: expected symbol in context {}, got: {}",
+                context.as_str(),
+                symbol.as_str()
+            )));
+        }
+
+        let name: &str = symbol.symbol_name().as_str();
+
+        E::from_str(name).map_err(|err| {
+            Error::custom(format!(
+                "get_symbol_enum: unrecognized symbol {}: {}",
+                symbol.as_str(),
+                err
+            ))
+        })
+    }
+
     //==================================
     // Strings
     //==================================
@@ -281,6 +500,8 @@ impl Link {
 
         let num_bytes = usize::try_from(num_bytes).unwrap();
 
+        link_state::element_read(self.raw_link);
+
         Ok(LinkStr {
             link: self,
 
@@ -291,6 +512,91 @@ impl Link {
         })
     }
 
+    /// Read a string from this link in chunks of at most `chunk_size` bytes, calling
+    /// `callback` with the UTF-8 encoded data of each chunk.
+    ///
+    /// Only a single buffer of `chunk_size` bytes is allocated, so this function can be
+    /// used to process very large strings without holding the entire string in memory.
+    ///
+    /// Note that the boundaries between chunks may fall in the middle of a multi-byte
+    /// UTF-8 encoded character, so individual chunks are not necessarily valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_str("Hello, World!").unwrap();
+    ///
+    /// let mut chunks: Vec<Vec<u8>> = Vec::new();
+    ///
+    /// link.get_utf8_str_chunks(5, |chunk| chunks.push(chunk.to_vec()))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(chunks, [&b"Hello"[..], b", Wor", b"ld!"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `chunk_size` is 0.
+    ///
+    /// *WSTP C API Documentation:* [`WSGetUTF8Characters()`](https://reference.wolfram.com/language/ref/c/WSGetUTF8Characters.html)
+    pub fn get_utf8_str_chunks<F>(
+        &mut self,
+        chunk_size: usize,
+        mut callback: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&[u8]),
+    {
+        assert!(chunk_size > 0, "get_utf8_str_chunks: chunk_size must be non-zero");
+
+        let buffer_size = c_int::try_from(chunk_size).unwrap_or(c_int::MAX);
+
+        let type_ = self.raw_get_next()?;
+
+        if type_ != i32::from(sys::WSTKSTR) {
+            return Err(Error::custom(format!(
+                "get_utf8_str_chunks: expected string, got token of type {}",
+                type_
+            )));
+        }
+
+        let mut buffer: Vec<u8> = vec![0; buffer_size as usize];
+
+        loop {
+            let mut chars_left: c_int = 0;
+            let mut got: c_int = 0;
+
+            if unsafe {
+                sys::WSGetUTF8Characters(
+                    self.raw_link,
+                    &mut chars_left,
+                    buffer.as_mut_ptr(),
+                    buffer_size,
+                    &mut got,
+                )
+            } == 0
+            {
+                return Err(self.error_or_unknown());
+            }
+
+            let got = usize::try_from(got).unwrap();
+
+            callback(&buffer[..got]);
+
+            if chars_left == 0 {
+                break;
+            }
+        }
+
+        link_state::element_read(self.raw_link);
+
+        Ok(())
+    }
+
     /// *WSTP C API Documentation:* [`WSGetUTF16String()`](https://reference.wolfram.com/language/ref/c/WSGetUTF16String.html)
     pub fn get_utf16_str<'link>(
         &'link mut self,
@@ -310,6 +616,8 @@ impl Link {
 
         let num_elems = usize::try_from(num_elems).unwrap();
 
+        link_state::element_read(self.raw_link);
+
         Ok(LinkStr {
             link: self,
 
@@ -336,6 +644,8 @@ impl Link {
 
         let num_elems = usize::try_from(num_elems).unwrap();
 
+        link_state::element_read(self.raw_link);
+
         Ok(LinkStr {
             link: self,
 
@@ -346,10 +656,83 @@ impl Link {
         })
     }
 
+    /// Read a string containing a single character.
+    ///
+    /// Returns an error if the string is empty or contains more than one Unicode
+    /// scalar value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_char('λ').unwrap();
+    ///
+    /// assert_eq!(link.get_char(), Ok('λ'));
+    /// ```
+    pub fn get_char(&mut self) -> Result<char, Error> {
+        let string = self.get_string_ref()?;
+        let string: &str = string.get();
+
+        let mut chars = string.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(Error::custom(format!(
+                "expected string containing a single character, got: {:?}",
+                string
+            ))),
+        }
+    }
+
     //==================================
     // Functions
     //==================================
 
+    /// Get the number of functions that are currently being read from this link.
+    ///
+    /// The depth is incremented when a function is entered using
+    /// [`Link::get_arg_count()`] (e.g. via [`Link::get_token()`]) or
+    /// [`Link::test_head()`], and decremented when the last element of that function
+    /// has been read. It is reset to 0 by [`Link::new_packet()`] and
+    /// [`Link::raw_next_packet()`].
+    ///
+    /// This is intended for diagnosing a reader that has lost its place in the
+    /// incoming data. The depth is tracked on a best-effort basis by this crate, because
+    /// WSTP does not report it: data read from the underlying `WSLINK` using raw `sys`
+    /// functions is not counted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// // Put {1, f[2]}
+    /// link.put_function("System`List", 2).unwrap();
+    /// link.put_i64(1).unwrap();
+    /// link.put_function("Global`f", 1).unwrap();
+    /// link.put_i64(2).unwrap();
+    ///
+    /// assert_eq!(link.read_depth(), 0);
+    ///
+    /// assert_eq!(link.test_head("System`List"), Ok(2));
+    /// assert_eq!(link.get_i64(), Ok(1));
+    /// assert_eq!(link.read_depth(), 1);
+    ///
+    /// assert_eq!(link.test_head("Global`f"), Ok(1));
+    /// assert_eq!(link.read_depth(), 2);
+    ///
+    /// assert_eq!(link.get_i64(), Ok(2));
+    /// assert_eq!(link.read_depth(), 0);
+    /// ```
+    pub fn read_depth(&self) -> usize {
+        link_state::with(self.raw_link, |state| state.read_stack.len())
+    }
+
     /// Check that the incoming expression is a function with head `symbol`.
     ///
     /// If the check succeeds, the number of elements in the incoming expression is
@@ -397,7 +780,33 @@ impl Link {
 
     /// Check that the incoming expression is a function with head `symbol`.
     ///
-    /// This method is an optimized variant of [`Link::test_head()`].
+    /// This method is an optimized variant of [`Link::test_head()`]. Unlike
+    /// [`Link::test_head()`], it does not need to allocate a [`CString`] on each call,
+    /// so it is well suited to loops that read many functions with the same head. The
+    /// `CStr` for the head can be created once and reused.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::ffi::CStr;
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// for i in 0..3 {
+    ///     link.put_function("System`Quantity", 2).unwrap();
+    ///     link.put_i64(i).unwrap();
+    ///     link.put_str("Seconds").unwrap();
+    /// }
+    ///
+    /// let quantity = CStr::from_bytes_with_nul(b"System`Quantity\0").unwrap();
+    ///
+    /// for i in 0..3 {
+    ///     assert_eq!(link.test_head_cstr(quantity), Ok(2));
+    ///     assert_eq!(link.get_i64(), Ok(i));
+    ///     assert_eq!(link.get_string().unwrap(), "Seconds");
+    /// }
+    /// ```
     pub fn test_head_cstr(&mut self, symbol: &CStr) -> Result<usize, Error> {
         let mut len: std::os::raw::c_int = 0;
 
@@ -407,9 +816,65 @@ impl Link {
 
         let len = usize::try_from(len).expect("c_int overflows usize");
 
+        link_state::enter_function(self.raw_link, len);
+
         Ok(len)
     }
 
+    /// Check that the incoming expression is a function with head `symbol`, and read
+    /// all of its arguments as [`Expr`]s.
+    ///
+    /// This is equivalent to calling [`Link::test_head()`] followed by
+    /// [`Link::get_expr()`] once for each argument.
+    ///
+    /// # Errors
+    ///
+    /// The message of the returned error distinguishes between an incoming expression
+    /// that does not have head `symbol`, and a failure to read one of the arguments.
+    /// The WSTP error code, if any, is preserved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    /// use wolfram_expr::Expr;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    /// link.put_function("System`Quantity", 2).unwrap();
+    /// link.put_f64(5.0).unwrap();
+    /// link.put_str("Seconds").unwrap();
+    ///
+    /// assert_eq!(
+    ///     link.get_args_of("System`Quantity"),
+    ///     Ok(vec![Expr::real(5.0), Expr::string("Seconds")])
+    /// );
+    /// ```
+    pub fn get_args_of(&mut self, symbol: &str) -> Result<Vec<Expr>, Error> {
+        let argc = self.test_head(symbol).map_err(|err| {
+            err.with_context(format!(
+                "get_args_of: expected function with head {}",
+                symbol
+            ))
+        })?;
+
+        let mut args = Vec::with_capacity(argc);
+
+        for index in 0..argc {
+            let arg = self.get_expr().map_err(|err| {
+                err.with_context(format!(
+                    "get_args_of: error reading argument {} of {} to {}",
+                    index + 1,
+                    argc,
+                    symbol
+                ))
+            })?;
+
+            args.push(arg);
+        }
+
+        Ok(args)
+    }
+
     /// *WSTP C API Documentation:* [`WSGetArgCount()`](https://reference.wolfram.com/language/ref/c/WSGetArgCount.html)
     pub fn get_arg_count(&mut self) -> Result<usize, Error> {
         let mut arg_count = 0;
@@ -424,113 +889,1167 @@ impl Link {
             // numeric environment handling issue.
             .expect("WSTKFUNC argument count could not be converted to usize");
 
+        // The head of the function is read next, followed by its arguments.
+        link_state::enter_function(self.raw_link, arg_count + 1);
+
         Ok(arg_count)
     }
 
     //==================================
-    // Numerics
+    // Quantity
     //==================================
 
-    /// *WSTP C API Documentation:* [`WSGetInteger64()`](https://reference.wolfram.com/language/ref/c/WSGetInteger64.html)
-    pub fn get_i64(&mut self) -> Result<i64, Error> {
-        let mut int = 0;
-        if unsafe { WSGetInteger64(self.raw_link, &mut int) } == 0 {
-            return Err(self.error_or_unknown());
-        }
-        Ok(int)
-    }
-
-    /// *WSTP C API Documentation:* [`WSGetInteger32()`](https://reference.wolfram.com/language/ref/c/WSGetInteger32.html)
-    pub fn get_i32(&mut self) -> Result<i32, Error> {
-        let mut int = 0;
-        if unsafe { WSGetInteger32(self.raw_link, &mut int) } == 0 {
-            return Err(self.error_or_unknown());
-        }
-        Ok(int)
-    }
-
-    /// *WSTP C API Documentation:* [`WSGetInteger16()`](https://reference.wolfram.com/language/ref/c/WSGetInteger16.html)
-    pub fn get_i16(&mut self) -> Result<i16, Error> {
-        let mut int = 0;
-        if unsafe { WSGetInteger16(self.raw_link, &mut int) } == 0 {
-            return Err(self.error_or_unknown());
+    /// Read a [`Quantity[value, "unit"]`](https://reference.wolfram.com/language/ref/Quantity.html)
+    /// expression from this link, returning the value and unit.
+    ///
+    /// `value` may be an integer or a real; integers are converted to [`f64`], like
+    /// [`Link::get_f64_coerce()`]. An error is returned if the unit is not a string,
+    /// e.g. for compound units like `"Meters"/"Seconds"`.
+    ///
+    /// The `Quantity` head may be given with or without the `` System` `` context,
+    /// which is how the Kernel sends it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_quantity(9.8, "Meters").unwrap();
+    ///
+    /// assert_eq!(link.get_quantity(), Ok((9.8, "Meters".to_owned())));
+    /// ```
+    pub fn get_quantity(&mut self) -> Result<(f64, String), Error> {
+        let argc = self.get_system_function("Quantity")?;
+
+        if argc != 2 {
+            return Err(Error::custom(format!(
+                "get_quantity: expected Quantity with 2 arguments, got {}",
+                argc
+            )));
         }
-        Ok(int)
-    }
 
-    /// *WSTP C API Documentation:* [`WSGetInteger8()`](https://reference.wolfram.com/language/ref/c/WSGetInteger8.html)
-    pub fn get_u8(&mut self) -> Result<u8, Error> {
-        let mut int = 0;
-        if unsafe { WSGetInteger8(self.raw_link, &mut int) } == 0 {
-            return Err(self.error_or_unknown());
-        }
-        Ok(int)
-    }
+        let value = self
+            .get_f64_coerce()
+            .map_err(|err| err.with_context("get_quantity: error reading value"))?;
 
-    /// *WSTP C API Documentation:* [`WSGetReal64()`](https://reference.wolfram.com/language/ref/c/WSGetReal64.html)
-    pub fn get_f64(&mut self) -> Result<f64, Error> {
-        let mut real: f64 = 0.0;
-        if unsafe { WSGetReal64(self.raw_link, &mut real) } == 0 {
-            return Err(self.error_or_unknown());
-        }
-        Ok(real)
-    }
+        let unit = self
+            .get_string()
+            .map_err(|err| err.with_context("get_quantity: error reading unit"))?;
 
-    /// *WSTP C API Documentation:* [`WSGetReal32()`](https://reference.wolfram.com/language/ref/c/WSGetReal32.html)
-    pub fn get_f32(&mut self) -> Result<f32, Error> {
-        let mut real: f32 = 0.0;
-        if unsafe { WSGetReal32(self.raw_link, &mut real) } == 0 {
-            return Err(self.error_or_unknown());
-        }
-        Ok(real)
+        Ok((value, unit))
     }
 
     //==================================
-    // Integer numeric arrays
+    // Associations
     //==================================
 
-    /// Get a multidimensional array of [`i64`].
+    /// Read an [`Association`](https://reference.wolfram.com/language/ref/Association.html)
+    /// from this link, using `get_key` and `get_value` to read each key and value.
+    ///
+    /// The entries are collected into any collection that implements
+    /// [`FromIterator<(K, V)>`][FromIterator], such as a
+    /// [`HashMap`][std::collections::HashMap] or [`BTreeMap`][std::collections::BTreeMap].
+    /// Each element of the association must be a `key -> value` rule.
+    ///
+    /// See also [`Link::put_association()`].
     ///
     /// # Example
     ///
     /// ```
+    /// use std::collections::HashMap;
     /// use wstp::Link;
     ///
     /// let mut link = Link::new_loopback().unwrap();
     ///
-    /// link.put_i64_array(&[1, 2, 3, 4], &[2, 2]).unwrap();
+    /// // <|"a" -> 1, "b" -> 2|>
+    /// link.put_function("System`Association", 2).unwrap();
+    /// link.put_function("System`Rule", 2).unwrap();
+    /// link.put_str("a").unwrap();
+    /// link.put_i64(1).unwrap();
+    /// link.put_function("System`Rule", 2).unwrap();
+    /// link.put_str("b").unwrap();
+    /// link.put_i64(2).unwrap();
     ///
-    /// let out = link.get_i64_array().unwrap();
+    /// let map: HashMap<String, i64> =
+    ///     link.get_association(Link::get_string, Link::get_i64).unwrap();
     ///
-    /// assert_eq!(out.data().len(), 4);
-    /// assert_eq!(out.dimensions(), &[2, 2]);
+    /// assert_eq!(map, HashMap::from([("a".to_owned(), 1), ("b".to_owned(), 2)]));
     /// ```
-    ///
-    /// *WSTP C API Documentation:* [`WSGetInteger64Array()`](https://reference.wolfram.com/language/ref/c/WSGetInteger64Array.html)
-    pub fn get_i64_array(&mut self) -> Result<Array<i64>, Error> {
-        unsafe { self.get_array(sys::WSGetInteger64Array, sys::WSReleaseInteger64Array) }
+    pub fn get_association<C, K, V, FK, FV>(
+        &mut self,
+        mut get_key: FK,
+        mut get_value: FV,
+    ) -> Result<C, Error>
+    where
+        C: FromIterator<(K, V)>,
+        FK: FnMut(&mut Link) -> Result<K, Error>,
+        FV: FnMut(&mut Link) -> Result<V, Error>,
+    {
+        let count = self.get_system_function("Association")?;
+
+        let mut entries: Vec<(K, V)> = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            let arg_count = self.get_system_function("Rule")?;
+
+            if arg_count != 2 {
+                return Err(Error::custom(format!(
+                    "get_association: expected Rule with 2 arguments, got {}",
+                    arg_count
+                )));
+            }
+
+            let key = get_key(self)?;
+            let value = get_value(self)?;
+
+            entries.push((key, value));
+        }
+
+        Ok(C::from_iter(entries))
+    }
+
+    /// Read the head of an incoming function whose head is the `` System` `` symbol
+    /// `name`, returning the function's argument count.
+    ///
+    /// Unlike [`Link::test_head()`], this accepts both the fully qualified symbol name
+    /// and the bare `name`, which is how the Kernel sends `` System` `` symbols.
+    pub(crate) fn get_system_function(&mut self, name: &str) -> Result<usize, Error> {
+        let type_ = self.get_type()?;
+
+        if type_ != TokenType::Function {
+            return Err(Error::custom(format!(
+                "expected {}[..] expression, got token of type {:?}",
+                name, type_
+            )));
+        }
+
+        let arg_count = self.get_arg_count()?;
+
+        let head = self.get_symbol_ref()?;
+        let head: &str = head.as_str();
+
+        if head != name && head.strip_prefix("System`") != Some(name) {
+            return Err(Error::custom(format!(
+                "expected {}[..] expression, got head: {}",
+                name, head
+            )));
+        }
+
+        Ok(arg_count)
+    }
+
+    //==================================
+    // Null and Missing
+    //==================================
+
+    /// Read an optional value from this link.
+    ///
+    /// If the incoming expression is the symbol
+    /// [`Null`](https://reference.wolfram.com/language/ref/Null.html) or a
+    /// [`Missing[...]`](https://reference.wolfram.com/language/ref/Missing.html)
+    /// expression, it is skipped and `None` is returned. Otherwise, `get` is called to
+    /// read the incoming expression.
+    ///
+    /// See also [`Link::put_null()`] and [`Link::put_missing()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_i64(5).unwrap();
+    /// link.put_null().unwrap();
+    /// link.put_missing(Some("NotAvailable")).unwrap();
+    ///
+    /// assert_eq!(link.get_optional(Link::get_i64), Ok(Some(5)));
+    /// assert_eq!(link.get_optional(Link::get_i64), Ok(None));
+    /// assert_eq!(link.get_optional(Link::get_i64), Ok(None));
+    /// ```
+    pub fn get_optional<T, F>(&mut self, get: F) -> Result<Option<T>, Error>
+    where
+        F: FnOnce(&mut Link) -> Result<T, Error>,
+    {
+        if self.peek(Link::is_null_or_missing)?? {
+            let () = self.skip_exprs(1)?;
+            return Ok(None);
+        }
+
+        get(self).map(Some)
+    }
+
+    /// Returns `true` if the incoming expression is `Null` or `Missing[...]`.
+    ///
+    /// This function advances the link, and should be called via [`Link::peek()`].
+    fn is_null_or_missing(&mut self) -> Result<bool, Error> {
+        let is_null_or_missing = match self.get_type()? {
+            TokenType::Symbol => {
+                matches!(self.get_symbol_ref()?.as_str(), "System`Null" | "Null")
+            },
+            TokenType::Function => {
+                let _: usize = self.get_arg_count()?;
+
+                match self.get_type()? {
+                    TokenType::Symbol => matches!(
+                        self.get_symbol_ref()?.as_str(),
+                        "System`Missing" | "Missing"
+                    ),
+                    _ => false,
+                }
+            },
+            TokenType::Integer | TokenType::Real | TokenType::String => false,
+        };
+
+        Ok(is_null_or_missing)
+    }
+
+    //==================================
+    // Numerics
+    //==================================
+
+    /// *WSTP C API Documentation:* [`WSGetInteger64()`](https://reference.wolfram.com/language/ref/c/WSGetInteger64.html)
+    pub fn get_i64(&mut self) -> Result<i64, Error> {
+        let mut int = 0;
+        if unsafe { WSGetInteger64(self.raw_link, &mut int) } == 0 {
+            return Err(self.error_or_unknown());
+        }
+        link_state::element_read(self.raw_link);
+        Ok(int)
+    }
+
+    /// *WSTP C API Documentation:* [`WSGetInteger32()`](https://reference.wolfram.com/language/ref/c/WSGetInteger32.html)
+    pub fn get_i32(&mut self) -> Result<i32, Error> {
+        let mut int = 0;
+        if unsafe { WSGetInteger32(self.raw_link, &mut int) } == 0 {
+            return Err(self.error_or_unknown());
+        }
+        link_state::element_read(self.raw_link);
+        Ok(int)
+    }
+
+    /// *WSTP C API Documentation:* [`WSGetInteger16()`](https://reference.wolfram.com/language/ref/c/WSGetInteger16.html)
+    pub fn get_i16(&mut self) -> Result<i16, Error> {
+        let mut int = 0;
+        if unsafe { WSGetInteger16(self.raw_link, &mut int) } == 0 {
+            return Err(self.error_or_unknown());
+        }
+        link_state::element_read(self.raw_link);
+        Ok(int)
+    }
+
+    /// *WSTP C API Documentation:* [`WSGetInteger8()`](https://reference.wolfram.com/language/ref/c/WSGetInteger8.html)
+    pub fn get_u8(&mut self) -> Result<u8, Error> {
+        let mut int = 0;
+        if unsafe { WSGetInteger8(self.raw_link, &mut int) } == 0 {
+            return Err(self.error_or_unknown());
+        }
+        link_state::element_read(self.raw_link);
+        Ok(int)
+    }
+
+    /// Get a signed 8-bit integer.
+    ///
+    /// WSTP's 8-bit integer functions are unsigned, so the integer is read using
+    /// [`WSGetInteger16()`](https://reference.wolfram.com/language/ref/c/WSGetInteger16.html).
+    /// An error is returned if the integer is outside the range of [`i8`].
+    pub fn get_i8(&mut self) -> Result<i8, Error> {
+        let int: i16 = self.get_i16()?;
+
+        i8::try_from(int).map_err(|_| {
+            Error::custom(format!("get_i8: integer {} is out of range for i8", int))
+        })
+    }
+
+    /// *WSTP C API Documentation:* [`WSGetReal64()`](https://reference.wolfram.com/language/ref/c/WSGetReal64.html)
+    pub fn get_f64(&mut self) -> Result<f64, Error> {
+        let mut real: f64 = 0.0;
+        if unsafe { WSGetReal64(self.raw_link, &mut real) } == 0 {
+            return Err(self.error_or_unknown());
+        }
+        link_state::element_read(self.raw_link);
+        Ok(real)
+    }
+
+    /// Read a real or integer from this link, returning it as an [`f64`].
+    ///
+    /// Wolfram Language results that are mathematically real are often exact integers,
+    /// e.g. `Mean[{1, 2, 3}]` evaluates to `2`, not `2.`. Unlike [`Link::get_f64()`],
+    /// this function accepts an integer token, and converts it to the nearest [`f64`].
+    ///
+    /// An error is returned if the next token is not a real or an integer, or is an
+    /// integer that does not fit in an [`i64`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_i64(2).unwrap();
+    /// link.put_f64(2.5).unwrap();
+    ///
+    /// assert_eq!(link.get_f64_coerce(), Ok(2.0));
+    /// assert_eq!(link.get_f64_coerce(), Ok(2.5));
+    /// ```
+    pub fn get_f64_coerce(&mut self) -> Result<f64, Error> {
+        match self.get_type()? {
+            TokenType::Integer => Ok(self.get_i64()? as f64),
+            TokenType::Real => self.get_f64(),
+            other => Err(Error::custom(format!(
+                "get_f64_coerce: expected Real or Integer, got {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Read a real number from this link as a string, without converting it to an
+    /// [`f64`].
+    ///
+    /// Arbitrary-precision reals sent by the Wolfram Kernel can have more digits than
+    /// an [`f64`] is able to represent, which [`Link::get_f64()`] would discard. The
+    /// returned string contains every digit that was sent, in the textual form used by
+    /// WSTP (which may include a precision or exponent mark, like ``1.5`20.`` or
+    /// `1.5*^10`).
+    ///
+    /// An error is returned if the next token is not a real.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_f64(2.5).unwrap();
+    ///
+    /// assert!(link.get_real_string().unwrap().starts_with("2.5"));
+    /// ```
+    ///
+    /// *WSTP C API Documentation:* [`WSGetNumberAsString()`](https://reference.wolfram.com/language/ref/c/WSGetNumberAsString.html)
+    pub fn get_real_string(&mut self) -> Result<String, Error> {
+        let token_type = self.get_type()?;
+
+        if token_type != TokenType::Real {
+            return Err(Error::custom(format!(
+                "get_real_string: expected Real, got {:?}",
+                token_type
+            )));
+        }
+
+        self.get_number_string()
+    }
+
+    /// Determine the number of bits needed to store the integer available to read from
+    /// this link, without consuming it.
+    ///
+    /// Returns the size in bits of the smallest of [`i8`], [`i16`], [`i32`], and
+    /// [`i64`] that can hold the integer. Integers too large for an [`i64`] return the
+    /// number of bits needed to store them as a two's complement integer, rounded up to
+    /// a multiple of 64.
+    ///
+    /// `None` is returned if the next token is not an integer.
+    ///
+    /// WSTP does not transmit the type an integer was written with, so the width is
+    /// determined from the value of the integer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_i64(100).unwrap();
+    ///
+    /// assert_eq!(link.peek_integer_bits(), Ok(Some(8)));
+    ///
+    /// // The integer is still available to read.
+    /// assert_eq!(link.get_i8(), Ok(100));
+    /// ```
+    pub fn peek_integer_bits(&mut self) -> Result<Option<u32>, Error> {
+        self.peek(|link: &mut Link| -> Result<Option<u32>, Error> {
+            if link.get_type()? != TokenType::Integer {
+                return Ok(None);
+            }
+
+            let digits = link.get_number_string()?;
+
+            integer_bits(&digits).map(Some)
+        })?
+    }
+
+    /// Read the next number on this link in its textual form.
+    ///
+    /// *WSTP C API Documentation:* [`WSGetNumberAsString()`](https://reference.wolfram.com/language/ref/c/WSGetNumberAsString.html)
+    fn get_number_string(&mut self) -> Result<String, Error> {
+        let mut ptr: *const c_char = std::ptr::null();
+
+        if unsafe { sys::WSGetNumberAsString(self.raw_link, &mut ptr) } == 0 {
+            return Err(self.error_or_unknown());
+        }
+
+        link_state::element_read(self.raw_link);
+
+        let string = unsafe { CStr::from_ptr(ptr) }
+            .to_str()
+            .map(ToOwned::to_owned)
+            .map_err(|err| Error::custom(format!("number is not UTF-8: {}", err)));
+
+        unsafe { sys::WSReleaseString(self.raw_link, ptr) };
+
+        string
+    }
+
+    /// *WSTP C API Documentation:* [`WSGetReal32()`](https://reference.wolfram.com/language/ref/c/WSGetReal32.html)
+    pub fn get_f32(&mut self) -> Result<f32, Error> {
+        let mut real: f32 = 0.0;
+        if unsafe { WSGetReal32(self.raw_link, &mut real) } == 0 {
+            return Err(self.error_or_unknown());
+        }
+        link_state::element_read(self.raw_link);
+        Ok(real)
+    }
+
+    //==================================
+    // Array introspection
+    //==================================
+
+    /// Determine the element type of the numeric array available to read from this
+    /// link, without reading it.
+    ///
+    /// `None` is returned if the next expression is not a list whose first leaf element
+    /// is an integer or real number.
+    ///
+    /// This can be used to choose which array getter to call when the element type
+    /// is not known ahead of time, instead of calling a getter that would convert the
+    /// elements (see [`Array`] for details of the conversions performed).
+    ///
+    /// Only the first element of the array is examined. Use the `_exact` array getters,
+    /// like [`Link::get_i64_array_exact()`], to verify that every element has the
+    /// expected type.
+    ///
+    /// # Element widths
+    ///
+    /// WSTP converts between numeric types of different widths as arrays are read, and
+    /// the documented WSTP API does not report the width that was used when the array
+    /// was written. So, for example, an array written using [`Link::put_i16_array()`]
+    /// and one written using [`Link::put_i64_array()`] both have an element type of
+    /// [`ArrayElementType::Integer`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::{ArrayElementType, Link};
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_f64_array(&[1.5, 2.5], &[2]).unwrap();
+    ///
+    /// assert_eq!(link.peek_array_type(), Ok(Some(ArrayElementType::Real)));
+    ///
+    /// // The array is still available to read.
+    /// assert_eq!(link.get_f64_array().unwrap().data(), &[1.5, 2.5]);
+    ///
+    /// link.put_str("not an array").unwrap();
+    ///
+    /// assert_eq!(link.peek_array_type(), Ok(None));
+    /// ```
+    pub fn peek_array_type(&mut self) -> Result<Option<ArrayElementType>, Error> {
+        self.peek(|link: &mut Link| -> Result<Option<ArrayElementType>, Error> {
+            if link.get_type()? != TokenType::Function {
+                return Ok(None);
+            }
+
+            // Walk down the first element at each level of the array.
+            loop {
+                match link.get_type()? {
+                    TokenType::Function => {
+                        if link.get_arg_count()? == 0 {
+                            return Ok(None);
+                        }
+
+                        // Skip the head.
+                        link.skip_exprs(1)?;
+                    },
+                    TokenType::Integer => return Ok(Some(ArrayElementType::Integer)),
+                    TokenType::Real => return Ok(Some(ArrayElementType::Real)),
+                    TokenType::String | TokenType::Symbol => return Ok(None),
+                }
+            }
+        })?
+    }
+
+    //==================================
+    // Array size limits
+    //==================================
+
+    /// Limit the number of elements that the array getters on this link will read.
+    ///
+    /// After this is called, the array getter methods, like [`Link::get_i64_array()`]
+    /// and [`Link::get_complex_f64_array()`], will return an error without reading the
+    /// array if the incoming array has more than `max` elements. This can be used to
+    /// protect a program reading from an untrusted link from allocating an unbounded
+    /// amount of memory.
+    ///
+    /// The number of elements is computed from the argument counts of the nested lists
+    /// that make up the array, which are read before the elements of the array are
+    /// read. If the limit is exceeded, the array is not consumed from the link.
+    ///
+    /// Pass [`usize::MAX`] to remove the limit. By default, no limit is applied.
+    ///
+    /// A limit set on a link passed to the function given to [`Link::with_borrowed()`]
+    /// is removed when that function returns. A limit set on a link borrowed using
+    /// [`Link::unchecked_ref_cast_mut()`] remains set on its `WSLINK` until a [`Link`]
+    /// that owns the `WSLINK` is dropped, so remove it before the borrow ends if the
+    /// `WSLINK` is owned by someone else.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.set_max_array_elements(4);
+    ///
+    /// link.put_i64_array(&[1, 2, 3, 4, 5, 6], &[2, 3]).unwrap();
+    ///
+    /// assert!(link.get_i64_array().is_err());
+    ///
+    /// link.set_max_array_elements(usize::MAX);
+    ///
+    /// assert_eq!(link.get_i64_array().unwrap().data(), &[1, 2, 3, 4, 5, 6]);
+    /// ```
+    pub fn set_max_array_elements(&mut self, max: usize) {
+        link_state::update(self.raw_link, |state| {
+            state.max_array_elements = if max == usize::MAX { None } else { Some(max) };
+        })
+    }
+
+    /// Get the limit set using [`Link::set_max_array_elements()`], if any.
+    pub fn max_array_elements(&self) -> Option<usize> {
+        link_state::with(self.raw_link, |state| state.max_array_elements)
+    }
+
+    /// Check that the incoming array does not exceed the limit set using
+    /// [`Link::set_max_array_elements()`].
+    fn check_array_elements_limit(&mut self) -> Result<(), Error> {
+        let max = match self.max_array_elements() {
+            Some(max) => max,
+            None => return Ok(()),
+        };
+
+        // Walk down the first element at each level of the array, multiplying the
+        // argument counts, without reading any of the array data.
+        let count: usize = self.peek(|link: &mut Link| -> Result<usize, Error> {
+            let mut count: usize = 1;
+
+            while link.get_type()? == TokenType::Function {
+                let length = link.get_arg_count()?;
+
+                count = count.saturating_mul(length);
+
+                if length == 0 {
+                    break;
+                }
+
+                // Skip the head.
+                link.skip_exprs(1)?;
+            }
+
+            Ok(count)
+        })??;
+
+        if count > max {
+            return Err(Error::custom(format!(
+                "array with {} elements exceeds the limit of {} elements",
+                count, max
+            )));
+        }
+
+        Ok(())
+    }
+
+    //==================================
+    // Integer numeric arrays
+    //==================================
+
+    /// Get a multidimensional array of [`i64`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_i64_array(&[1, 2, 3, 4], &[2, 2]).unwrap();
+    ///
+    /// let out = link.get_i64_array().unwrap();
+    ///
+    /// assert_eq!(out.data().len(), 4);
+    /// assert_eq!(out.dimensions(), &[2, 2]);
+    /// ```
+    ///
+    /// *WSTP C API Documentation:* [`WSGetInteger64Array()`](https://reference.wolfram.com/language/ref/c/WSGetInteger64Array.html)
+    pub fn get_i64_array(&mut self) -> Result<Array<i64>, Error> {
+        unsafe { self.get_array(sys::WSGetInteger64Array, sys::WSReleaseInteger64Array) }
+    }
+
+    /// Get a multidimensional array of [`i64`], copying its elements into a buffer in
+    /// the specified `order`.
+    ///
+    /// Returns the array elements and the dimensions of the array. With
+    /// [`ArrayOrder::RowMajor`], this is equivalent to copying the data of the array
+    /// returned by [`Link::get_i64_array()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::{ArrayOrder, Link};
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// // The 2x3 matrix {{1, 2, 3}, {4, 5, 6}}.
+    /// link.put_i64_array(&[1, 2, 3, 4, 5, 6], &[2, 3]).unwrap();
+    ///
+    /// assert_eq!(
+    ///     link.get_i64_array_with_order(ArrayOrder::ColumnMajor),
+    ///     Ok((vec![1, 4, 2, 5, 3, 6], vec![2, 3]))
+    /// );
+    /// ```
+    pub fn get_i64_array_with_order(
+        &mut self,
+        order: ArrayOrder,
+    ) -> Result<(Vec<i64>, Vec<usize>), Error> {
+        let array = self.get_i64_array()?;
+
+        let data: Vec<i64> = match order {
+            ArrayOrder::RowMajor => array.data().to_vec(),
+            ArrayOrder::ColumnMajor => to_column_major(array.data(), array.dimensions()),
+        };
+
+        Ok((data, array.dimensions().to_vec()))
+    }
+
+    /// *WSTP C API Documentation:* [`WSGetInteger32Array()`](https://reference.wolfram.com/language/ref/c/WSGetInteger32Array.html)
+    pub fn get_i32_array(&mut self) -> Result<Array<i32>, Error> {
+        unsafe { self.get_array(sys::WSGetInteger32Array, sys::WSReleaseInteger32Array) }
+    }
+
+    /// *WSTP C API Documentation:* [`WSGetInteger16Array()`](https://reference.wolfram.com/language/ref/c/WSGetInteger16Array.html)
+    pub fn get_i16_array(&mut self) -> Result<Array<i16>, Error> {
+        unsafe { self.get_array(sys::WSGetInteger16Array, sys::WSReleaseInteger16Array) }
+    }
+
+    /// *WSTP C API Documentation:* [`WSGetInteger8Array()`](https://reference.wolfram.com/language/ref/c/WSGetInteger8Array.html)
+    pub fn get_u8_array(&mut self) -> Result<Array<u8>, Error> {
+        unsafe { self.get_array(sys::WSGetInteger8Array, sys::WSReleaseInteger8Array) }
+    }
+
+    //==================================
+    // Floating-point numeric arrays
+    //==================================
+
+    /// Get a multidimensional array of [`f64`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_f64_array(&[3.141, 1.618, 2.718], &[3]).unwrap();
+    ///
+    /// let out = link.get_f64_array().unwrap();
+    ///
+    /// assert_eq!(out.data().len(), 3);
+    /// assert_eq!(out.data(), &[3.141, 1.618, 2.718]);
+    /// assert_eq!(out.dimensions(), &[3]);
+    /// ```
+    ///
+    /// *WSTP C API Documentation:* [`WSGetReal64Array()`](https://reference.wolfram.com/language/ref/c/WSGetReal64Array.html)
+    pub fn get_f64_array(&mut self) -> Result<Array<f64>, Error> {
+        unsafe { self.get_array(sys::WSGetReal64Array, sys::WSReleaseReal64Array) }
+    }
+
+    /// *WSTP C API Documentation:* [`WSGetReal32Array()`](https://reference.wolfram.com/language/ref/c/WSGetReal32Array.html)
+    pub fn get_f32_array(&mut self) -> Result<Array<f32>, Error> {
+        unsafe { self.get_array(sys::WSGetReal32Array, sys::WSReleaseReal32Array) }
+    }
+
+    /// Maximum number of elements passed to the callback of
+    /// [`Link::get_f64_array_streaming()`] at once.
+    pub const STREAMING_CHUNK_LEN: usize = 4096;
+
+    /// Read a multidimensional array of [`f64`], passing its elements to `func` in
+    /// chunks instead of collecting them into a single buffer.
+    ///
+    /// The elements are read one at a time, in row-major order, and `func` is called
+    /// with each chunk of up to [`Link::STREAMING_CHUNK_LEN`] elements as it fills up.
+    /// This makes it possible to process arrays that are too large to hold in memory
+    /// at once, e.g. by writing them to a file or socket as they are read. Integer
+    /// elements are converted to [`f64`].
+    ///
+    /// Returns the dimensions of the array. An error is returned if the incoming data
+    /// is not a rectangular array of numbers; `func` may already have been called with
+    /// some of the elements when the error is detected.
+    ///
+    /// Because the array is never materialized, the limit set by
+    /// [`Link::set_max_array_elements()`] does not apply.
+    ///
+    /// # Buffering by WSTP
+    ///
+    /// This function bounds the memory used by this crate, but not by the WSTP library.
+    /// An array written by the sender using a single call like
+    /// [`WSPutReal64Array()`](https://reference.wolfram.com/language/ref/c/WSPutReal64Array.html)
+    /// (including [`Link::put_f64_array()`]) may be received and buffered by WSTP as a
+    /// whole before the first element can be read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_f64_array(&[1.0, 2.0, 3.0, 4.0], &[2, 2]).unwrap();
+    ///
+    /// let mut sum = 0.0;
+    ///
+    /// let dimensions = link
+    ///     .get_f64_array_streaming(|chunk: &[f64]| sum += chunk.iter().sum::<f64>())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(dimensions, vec![2, 2]);
+    /// assert_eq!(sum, 10.0);
+    /// ```
+    pub fn get_f64_array_streaming<F>(&mut self, mut func: F) -> Result<Vec<usize>, Error>
+    where
+        F: FnMut(&[f64]),
+    {
+        let not_rectangular = || {
+            Error::custom("get_f64_array_streaming: array is not rectangular".to_owned())
+        };
+
+        // Read the start of a `List[...]` expression, returning its length.
+        //
+        // `test_head()` is not used because it compares the head name literally, and
+        // arrays written by `put_f64_array()` or sent by the Kernel have `List` heads
+        // without a context.
+        fn get_list_length(link: &mut Link) -> Result<usize, Error> {
+            let length = match link.get_token()? {
+                Token::Function { length } => length,
+                token => {
+                    return Err(Error::custom(format!(
+                        "get_f64_array_streaming: expected List, got {:?}",
+                        token
+                    )))
+                },
+            };
+
+            let head = link.get_symbol_ref()?;
+
+            match head.as_str() {
+                "List" | "System`List" => Ok(length),
+                other => Err(Error::custom(format!(
+                    "get_f64_array_streaming: expected List head, got {}",
+                    other
+                ))),
+            }
+        }
+
+        let length = get_list_length(self)?;
+
+        let mut dimensions: Vec<usize> = vec![length];
+        // Number of elements that remain to be read at each level of the array.
+        let mut stack: Vec<usize> = vec![length];
+        // The rank is determined by descending through the first element at each
+        // level, until a number (or an empty list) is encountered.
+        let mut rank_known = length == 0;
+
+        let mut chunk: Vec<f64> = Vec::with_capacity(Link::STREAMING_CHUNK_LEN);
+
+        while let Some(remaining) = stack.last_mut() {
+            if *remaining == 0 {
+                stack.pop();
+                continue;
+            }
+
+            *remaining -= 1;
+
+            // The level of the element about to be read.
+            let level = stack.len();
+
+            if !rank_known {
+                if self.get_type()? == TokenType::Function {
+                    let length = get_list_length(self)?;
+
+                    dimensions.push(length);
+                    stack.push(length);
+
+                    rank_known = length == 0;
+                    continue;
+                }
+
+                rank_known = true;
+            }
+
+            if level < dimensions.len() {
+                let length = get_list_length(self)?;
+
+                if length != dimensions[level] {
+                    return Err(not_rectangular());
+                }
+
+                stack.push(length);
+            } else {
+                if self.get_type()? == TokenType::Function {
+                    return Err(not_rectangular());
+                }
+
+                chunk.push(self.get_f64_coerce()?);
+
+                if chunk.len() == Link::STREAMING_CHUNK_LEN {
+                    func(&chunk);
+                    chunk.clear();
+                }
+            }
+        }
+
+        if !chunk.is_empty() {
+            func(&chunk);
+        }
+
+        Ok(dimensions)
+    }
+
+    //==================================
+    // Complex numeric arrays
+    //==================================
+
+    /// Get a multidimensional array of complex numbers, returning the array elements as
+    /// `(real, imaginary)` pairs along with the dimensions of the array.
+    ///
+    /// Each element of the incoming array may be a
+    /// [`Complex[re, im]`](https://reference.wolfram.com/language/ref/Complex.html)
+    /// value, or a real or integer number, which is widened to a complex number with an
+    /// imaginary part of zero. This means that real arrays can also be read using this
+    /// function.
+    ///
+    /// See also [`Link::put_complex_f64_array()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_complex_f64_array(&[(1.0, 2.0), (3.0, -4.0)], &[2, 1]).unwrap();
+    /// link.put_f64_array(&[1.5, 2.5], &[2]).unwrap();
+    ///
+    /// assert_eq!(
+    ///     link.get_complex_f64_array(),
+    ///     Ok((vec![(1.0, 2.0), (3.0, -4.0)], vec![2, 1]))
+    /// );
+    /// assert_eq!(
+    ///     link.get_complex_f64_array(),
+    ///     Ok((vec![(1.5, 0.0), (2.5, 0.0)], vec![2]))
+    /// );
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn get_complex_f64_array(
+        &mut self,
+    ) -> Result<(Vec<(f64, f64)>, Vec<usize>), Error> {
+        let () = self.check_array_elements_limit()?;
+
+        let array: Expr = self.get_system_expr()?;
+
+        flatten_array(&array, "get_complex_f64_array", "a number", complex_from_expr)
     }
 
-    /// *WSTP C API Documentation:* [`WSGetInteger32Array()`](https://reference.wolfram.com/language/ref/c/WSGetInteger32Array.html)
-    pub fn get_i32_array(&mut self) -> Result<Array<i32>, Error> {
-        unsafe { self.get_array(sys::WSGetInteger32Array, sys::WSReleaseInteger32Array) }
+    //==================================
+    // String arrays
+    //==================================
+
+    /// Get a multidimensional array of strings, returning the array elements in
+    /// row-major order along with the dimensions of the array.
+    ///
+    /// The incoming array must be a rectangular structure of nested `List`s whose
+    /// leaf elements are all strings.
+    ///
+    /// See also [`Link::put_string_array()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_string_array(&["a", "b", "c", "d"], &[2, 2]).unwrap();
+    ///
+    /// let (data, dimensions) = link.get_string_array().unwrap();
+    ///
+    /// assert_eq!(data, vec!["a", "b", "c", "d"]);
+    /// assert_eq!(dimensions, vec![2, 2]);
+    /// ```
+    pub fn get_string_array(&mut self) -> Result<(Vec<String>, Vec<usize>), Error> {
+        let () = self.check_array_elements_limit()?;
+
+        let array: Expr = self.get_system_expr()?;
+
+        flatten_array(&array, "get_string_array", "a string", |expr| {
+            expr.try_as_str().map(ToOwned::to_owned)
+        })
     }
 
-    /// *WSTP C API Documentation:* [`WSGetInteger16Array()`](https://reference.wolfram.com/language/ref/c/WSGetInteger16Array.html)
-    pub fn get_i16_array(&mut self) -> Result<Array<i16>, Error> {
-        unsafe { self.get_array(sys::WSGetInteger16Array, sys::WSReleaseInteger16Array) }
+    //==================================
+    // Sparse arrays
+    //==================================
+
+    /// Get a two-dimensional real
+    /// [`SparseArray`](https://reference.wolfram.com/language/ref/SparseArray.html),
+    /// returning the zero-based `(row, column)` indices of its nonzero elements, their
+    /// values, and the dimensions of the array.
+    ///
+    /// Both the rule-based form written by [`Link::put_sparse_array()`], and the
+    /// `SparseArray[Automatic, dims, default, {1, {rowPointers, columnIndices}, values}]`
+    /// form sent by the Wolfram Kernel are supported. Integer values are converted to
+    /// [`f64`].
+    ///
+    /// An error is returned if the array does not have rank two, has complex values, or
+    /// has a nonzero default value.
+    #[allow(clippy::type_complexity)]
+    pub fn get_sparse_array(
+        &mut self,
+    ) -> Result<(Vec<(usize, usize)>, Vec<f64>, (usize, usize)), Error> {
+        let expr: Expr = self.get_system_expr()?;
+
+        sparse_array_from_expr(&expr).ok_or_else(|| {
+            Error::custom(format!(
+                "get_sparse_array: expected a two-dimensional real SparseArray, got: {}",
+                expr
+            ))
+        })
     }
 
-    /// *WSTP C API Documentation:* [`WSGetInteger8Array()`](https://reference.wolfram.com/language/ref/c/WSGetInteger8Array.html)
-    pub fn get_u8_array(&mut self) -> Result<Array<u8>, Error> {
-        unsafe { self.get_array(sys::WSGetInteger8Array, sys::WSReleaseInteger8Array) }
+    //==================================
+    // NumericArray
+    //==================================
+
+    /// Get a [`NumericArray`](https://reference.wolfram.com/language/ref/NumericArray.html),
+    /// preserving its declared element type.
+    ///
+    /// The incoming expression must have the form `NumericArray[data, "type"]`, where
+    /// `data` is a rectangular array of numbers and `"type"` is the name of one of the
+    /// element types enumerated by [`NumericArrayData`]. An error is returned if any
+    /// element of `data` is not a number of the declared type: integer elements out of
+    /// range for the declared type, or real elements of an integer array, are not
+    /// converted.
+    ///
+    /// The `NumericArray` head may be given with or without the `` System` `` context,
+    /// which is how the Kernel sends it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::{Link, NumericArrayData};
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_function("System`NumericArray", 2).unwrap();
+    /// link.put_i64_array(&[1, 2, 3, 4], &[2, 2]).unwrap();
+    /// link.put_str("Integer8").unwrap();
+    ///
+    /// let array = link.get_numeric_array().unwrap();
+    ///
+    /// assert_eq!(array.type_name(), "Integer8");
+    /// assert_eq!(array.dimensions(), &[2, 2]);
+    /// assert_eq!(array.data(), &NumericArrayData::Integer8(vec![1, 2, 3, 4]));
+    /// ```
+    pub fn get_numeric_array(&mut self) -> Result<NumericArray, Error> {
+        let arg_count = self.get_system_function("NumericArray")?;
+
+        if arg_count != 2 {
+            return Err(Error::custom(format!(
+                "expected NumericArray with 2 arguments, got {} arguments",
+                arg_count
+            )));
+        }
+
+        // The element type follows the array data, so read it ahead of time to know
+        // which array getter to use.
+        let type_name: String = self.peek(|link: &mut Link| -> Result<String, Error> {
+            let () = link.skip_exprs(1)?;
+
+            link.get_string()
+        })??;
+
+        let is_real = matches!(type_name.as_str(), "Real32" | "Real64");
+
+        let (data, dimensions) = if is_real {
+            let array = self.get_f64_array_exact()?;
+
+            let data = match type_name.as_str() {
+                "Real32" => NumericArrayData::Real32(
+                    array.data().iter().map(|&real| real as f32).collect(),
+                ),
+                _ => NumericArrayData::Real64(array.data().to_vec()),
+            };
+
+            (data, array.dimensions().to_vec())
+        } else if type_name == "UnsignedInteger64" {
+            // Read these elements separately, because they may not fit in an `i64`.
+            let (data, dimensions) = self.get_u64_array()?;
+
+            (NumericArrayData::UnsignedInteger64(data), dimensions)
+        } else {
+            let array = self.get_i64_array_exact()?;
+            let ints: &[i64] = array.data();
+
+            let data = match type_name.as_str() {
+                "Integer8" => NumericArrayData::Integer8(convert_ints(ints, &type_name)?),
+                "Integer16" => {
+                    NumericArrayData::Integer16(convert_ints(ints, &type_name)?)
+                },
+                "Integer32" => {
+                    NumericArrayData::Integer32(convert_ints(ints, &type_name)?)
+                },
+                "Integer64" => NumericArrayData::Integer64(ints.to_vec()),
+                "UnsignedInteger8" => {
+                    NumericArrayData::UnsignedInteger8(convert_ints(ints, &type_name)?)
+                },
+                "UnsignedInteger16" => {
+                    NumericArrayData::UnsignedInteger16(convert_ints(ints, &type_name)?)
+                },
+                "UnsignedInteger32" => {
+                    NumericArrayData::UnsignedInteger32(convert_ints(ints, &type_name)?)
+                },
+                _ => {
+                    return Err(Error::custom(format!(
+                        "unsupported NumericArray element type: {:?}",
+                        type_name
+                    )))
+                },
+            };
+
+            (data, array.dimensions().to_vec())
+        };
+
+        // Consume the element type string, which has already been peeked at.
+        let _: LinkStr = self.get_string_ref()?;
+
+        Ok(NumericArray { data, dimensions })
+    }
+
+    /// Get a multidimensional array of [`u64`], returning the array data and its
+    /// dimensions.
+    ///
+    /// WSTP has no array getter for unsigned 64-bit integers, so the elements are read
+    /// one at a time in their textual form.
+    fn get_u64_array(&mut self) -> Result<(Vec<u64>, Vec<usize>), Error> {
+        let () = self.check_array_elements_limit()?;
+
+        // Walk down the first element at each level of the array to find its
+        // dimensions.
+        let dimensions: Vec<usize> =
+            self.peek(|link: &mut Link| -> Result<Vec<usize>, Error> {
+                let mut dimensions = Vec::new();
+
+                while link.get_type()? == TokenType::Function {
+                    let length = link.get_system_function("List")?;
+
+                    dimensions.push(length);
+
+                    if length == 0 {
+                        break;
+                    }
+                }
+
+                Ok(dimensions)
+            })??;
+
+        if dimensions.is_empty() {
+            return Err(Error::custom(
+                "expected NumericArray data to be a List".to_owned(),
+            ));
+        }
+
+        let mut data: Vec<u64> = Vec::new();
+
+        // The number of elements remaining to read at each level of the array.
+        let mut stack: Vec<usize> = vec![1];
+
+        while let Some(remaining) = stack.last_mut() {
+            if *remaining == 0 {
+                stack.pop();
+                continue;
+            }
+
+            *remaining -= 1;
+
+            // The first level of `stack` is the array itself.
+            let level = stack.len() - 1;
+
+            if level < dimensions.len() {
+                let length = self.get_system_function("List")?;
+
+                if length != dimensions[level] {
+                    return Err(Error::custom(
+                        "NumericArray data is not a rectangular array".to_owned(),
+                    ));
+                }
+
+                stack.push(length);
+                continue;
+            }
+
+            let type_ = self.get_type()?;
+
+            if type_ != TokenType::Integer {
+                return Err(Error::custom(format!(
+                    "NumericArray element of type {:?} cannot be read as \
+                     UnsignedInteger64",
+                    type_
+                )));
+            }
+
+            let digits = self.get_number_string()?;
+
+            let int: u64 = digits.parse().map_err(|_| {
+                Error::custom(format!(
+                    "NumericArray element {} is out of range for element type \
+                     UnsignedInteger64",
+                    digits
+                ))
+            })?;
+
+            data.push(int);
+        }
+
+        Ok((data, dimensions))
     }
 
     //==================================
-    // Floating-point numeric arrays
+    // Exact numeric arrays
     //==================================
 
-    /// Get a multidimensional array of [`f64`].
+    /// Get a multidimensional array of [`i64`], returning an error if any element of
+    /// the incoming array is not an integer.
+    ///
+    /// Unlike [`Link::get_i64_array()`], this function will not round real numbers to
+    /// the nearest integer. See the [`Array`] documentation for the conversions
+    /// performed by the non-exact array functions.
+    ///
+    /// The incoming array is read twice: once to check the type of each element, and
+    /// again to read the array data.
     ///
     /// # Example
     ///
@@ -541,21 +2060,123 @@ impl Link {
     ///
     /// link.put_f64_array(&[3.141, 1.618, 2.718], &[3]).unwrap();
     ///
-    /// let out = link.get_f64_array().unwrap();
+    /// assert!(link.get_i64_array_exact().is_err());
     ///
-    /// assert_eq!(out.data().len(), 3);
-    /// assert_eq!(out.data(), &[3.141, 1.618, 2.718]);
-    /// assert_eq!(out.dimensions(), &[3]);
+    /// // The array is not consumed if an error is returned.
+    /// assert_eq!(link.get_i64_array().unwrap().data(), &[3, 2, 3]);
     /// ```
+    pub fn get_i64_array_exact(&mut self) -> Result<Array<'_, i64>, Error> {
+        self.check_array_elements("i64", |elem| matches!(elem, Token::Integer(_)))?;
+
+        self.get_i64_array()
+    }
+
+    /// Get a multidimensional array of [`i32`], returning an error if any element of
+    /// the incoming array is not an integer that fits in an [`i32`].
     ///
-    /// *WSTP C API Documentation:* [`WSGetReal64Array()`](https://reference.wolfram.com/language/ref/c/WSGetReal64Array.html)
-    pub fn get_f64_array(&mut self) -> Result<Array<f64>, Error> {
-        unsafe { self.get_array(sys::WSGetReal64Array, sys::WSReleaseReal64Array) }
+    /// See [`Link::get_i64_array_exact()`].
+    pub fn get_i32_array_exact(&mut self) -> Result<Array<'_, i32>, Error> {
+        self.check_array_elements("i32", |elem| match elem {
+            Token::Integer(int) => i32::try_from(*int).is_ok(),
+            _ => false,
+        })?;
+
+        self.get_i32_array()
     }
 
-    /// *WSTP C API Documentation:* [`WSGetReal32Array()`](https://reference.wolfram.com/language/ref/c/WSGetReal32Array.html)
-    pub fn get_f32_array(&mut self) -> Result<Array<f32>, Error> {
-        unsafe { self.get_array(sys::WSGetReal32Array, sys::WSReleaseReal32Array) }
+    /// Get a multidimensional array of [`i16`], returning an error if any element of
+    /// the incoming array is not an integer that fits in an [`i16`].
+    ///
+    /// See [`Link::get_i64_array_exact()`].
+    pub fn get_i16_array_exact(&mut self) -> Result<Array<'_, i16>, Error> {
+        self.check_array_elements("i16", |elem| match elem {
+            Token::Integer(int) => i16::try_from(*int).is_ok(),
+            _ => false,
+        })?;
+
+        self.get_i16_array()
+    }
+
+    /// Get a multidimensional array of [`u8`], returning an error if any element of
+    /// the incoming array is not an integer that fits in a [`u8`].
+    ///
+    /// See [`Link::get_i64_array_exact()`].
+    pub fn get_u8_array_exact(&mut self) -> Result<Array<'_, u8>, Error> {
+        self.check_array_elements("u8", |elem| match elem {
+            Token::Integer(int) => u8::try_from(*int).is_ok(),
+            _ => false,
+        })?;
+
+        self.get_u8_array()
+    }
+
+    /// Get a multidimensional array of [`f64`], returning an error if any element of
+    /// the incoming array is not a real number.
+    ///
+    /// Unlike [`Link::get_f64_array()`], this function will not convert integers to
+    /// real numbers.
+    ///
+    /// See [`Link::get_i64_array_exact()`].
+    pub fn get_f64_array_exact(&mut self) -> Result<Array<'_, f64>, Error> {
+        self.check_array_elements("f64", |elem| matches!(elem, Token::Real(_)))?;
+
+        self.get_f64_array()
+    }
+
+    /// Get a multidimensional array of [`f32`], returning an error if any element of
+    /// the incoming array is not a real number that can be represented exactly as an
+    /// [`f32`].
+    ///
+    /// See [`Link::get_i64_array_exact()`].
+    pub fn get_f32_array_exact(&mut self) -> Result<Array<'_, f32>, Error> {
+        self.check_array_elements("f32", |elem| match elem {
+            Token::Real(real) => f64::from(*real as f32) == *real,
+            _ => false,
+        })?;
+
+        self.get_f32_array()
+    }
+
+    /// Check that `is_valid` returns `true` for every element of the incoming array,
+    /// without consuming the array.
+    fn check_array_elements<F>(&mut self, type_name: &str, is_valid: F) -> Result<(), Error>
+    where
+        F: Fn(&Token) -> bool,
+    {
+        let () = self.check_array_elements_limit()?;
+
+        // Walk the tokens of the array instead of reading it as an `Expr`, so that the
+        // context-less `List` heads used by arrays are accepted.
+        self.peek(|link: &mut Link| -> Result<(), Error> {
+            // The number of elements remaining to check at each level of the array.
+            let mut stack: Vec<usize> = vec![1];
+
+            while let Some(remaining) = stack.last_mut() {
+                if *remaining == 0 {
+                    stack.pop();
+                    continue;
+                }
+
+                *remaining -= 1;
+
+                let length = match link.get_token()? {
+                    Token::Function { length } => length,
+                    token if is_valid(&token) => continue,
+                    token => {
+                        return Err(Error::custom(format!(
+                            "array element {:?} cannot be read as {} without conversion",
+                            token, type_name
+                        )))
+                    },
+                };
+
+                // Skip the head.
+                link.skip_exprs(1)?;
+                stack.push(length);
+            }
+
+            Ok(())
+        })?
     }
 
     #[allow(non_snake_case)]
@@ -576,6 +2197,8 @@ impl Link {
             i32,
         ),
     ) -> Result<Array<T>, Error> {
+        let () = self.check_array_elements_limit()?;
+
         let Link { raw_link } = *self;
 
         let mut data_ptr: *mut T = std::ptr::null_mut();
@@ -597,6 +2220,8 @@ impl Link {
             return Err(self.error_or_unknown());
         }
 
+        link_state::element_read(raw_link);
+
         let depth: usize =
             usize::try_from(depth).expect("WSGet*Array depth overflows usize");
 
@@ -623,6 +2248,361 @@ impl Link {
     }
 }
 
+/// Copy the elements of the row-major array `data` into a column-major buffer.
+pub(crate) fn to_column_major<T: Copy>(data: &[T], dimensions: &[usize]) -> Vec<T> {
+    // Converting between row-major and column-major order transposes the indices of
+    // the array, so visiting the elements in row-major order of the reversed dimensions
+    // visits them in column-major order of `dimensions`.
+    let reversed: Vec<usize> = dimensions.iter().rev().copied().collect();
+
+    column_major_offsets(&reversed)
+        .into_iter()
+        .map(|offset| data[offset])
+        .collect()
+}
+
+/// Copy the elements of the column-major array `data` into a row-major buffer.
+pub(crate) fn from_column_major<T: Copy>(data: &[T], dimensions: &[usize]) -> Vec<T> {
+    column_major_offsets(dimensions)
+        .into_iter()
+        .map(|offset| data[offset])
+        .collect()
+}
+
+/// For each element of an array with `dimensions`, in row-major order, compute the
+/// offset of that element when the array is stored in column-major order.
+fn column_major_offsets(dimensions: &[usize]) -> Vec<usize> {
+    let len: usize = dimensions.iter().product();
+
+    // Column-major strides: the first index varies fastest.
+    let mut strides: Vec<usize> = Vec::with_capacity(dimensions.len());
+    let mut stride = 1;
+
+    for dim in dimensions {
+        strides.push(stride);
+        stride *= dim;
+    }
+
+    let mut index: Vec<usize> = vec![0; dimensions.len()];
+    let mut offsets: Vec<usize> = Vec::with_capacity(len);
+
+    for _ in 0..len {
+        offsets.push(index.iter().zip(&strides).map(|(i, stride)| i * stride).sum());
+
+        // Advance `index` in row-major order: the last index varies fastest.
+        for axis in (0..dimensions.len()).rev() {
+            index[axis] += 1;
+
+            if index[axis] < dimensions[axis] {
+                break;
+            }
+
+            index[axis] = 0;
+        }
+    }
+
+    offsets
+}
+
+/// Convert the elements of an integer array to the element type named `type_name`,
+/// returning an error if any element is out of range.
+fn convert_ints<T: TryFrom<i64>>(ints: &[i64], type_name: &str) -> Result<Vec<T>, Error> {
+    ints.iter()
+        .map(|&int| {
+            T::try_from(int).map_err(|_| {
+                Error::custom(format!(
+                    "NumericArray element {} is out of range for element type {}",
+                    int, type_name
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Compute the number of bits needed to store the decimal integer `digits`, as
+/// described by [`Link::peek_integer_bits()`].
+fn integer_bits(digits: &str) -> Result<u32, Error> {
+    let (negative, magnitude) = match digits.strip_prefix('-') {
+        Some(magnitude) => (true, magnitude),
+        None => (false, digits),
+    };
+
+    if magnitude.is_empty() || !magnitude.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(Error::custom(format!(
+            "unable to determine width of integer: {}",
+            digits
+        )));
+    }
+
+    // Little-endian base 2^32 representation of `magnitude`.
+    let mut limbs: Vec<u32> = vec![0];
+
+    for digit in magnitude.bytes().map(|byte| u64::from(byte - b'0')) {
+        let mut carry = digit;
+
+        for limb in limbs.iter_mut() {
+            let value = u64::from(*limb) * 10 + carry;
+            *limb = value as u32;
+            carry = value >> 32;
+        }
+
+        if carry != 0 {
+            limbs.push(carry as u32);
+        }
+    }
+
+    while limbs.len() > 1 && limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+
+    let top = *limbs.last().unwrap();
+    let magnitude_bits = (limbs.len() as u32 - 1) * 32 + (32 - top.leading_zeros());
+
+    // A sign bit is needed, except for negative powers of two, e.g. -128 fits in i8.
+    let is_power_of_two = top.is_power_of_two()
+        && limbs[..limbs.len() - 1].iter().all(|&limb| limb == 0);
+    let bits = if negative && is_power_of_two {
+        magnitude_bits
+    } else {
+        magnitude_bits + 1
+    };
+
+    let width = match bits {
+        0..=8 => 8,
+        9..=16 => 16,
+        17..=32 => 32,
+        _ => (bits + 63) / 64 * 64,
+    };
+
+    Ok(width)
+}
+
+/// Returns the elements of `expr` if it is a `List[...]` expression.
+fn list_elements(expr: &Expr) -> Option<&[Expr]> {
+    match expr.kind() {
+        ExprKind::Normal(normal) if normal.has_head(&Symbol::new("System`List")) => {
+            Some(normal.elements())
+        },
+        _ => None,
+    }
+}
+
+/// Flatten the rectangular array of nested `List`s `array` into its elements, in
+/// row-major order, and its dimensions.
+///
+/// `convert` is used to convert each leaf element of the array. `func_name` and
+/// `element_description` are used in error messages.
+fn flatten_array<T, F>(
+    array: &Expr,
+    func_name: &str,
+    element_description: &str,
+    convert: F,
+) -> Result<(Vec<T>, Vec<usize>), Error>
+where
+    F: Fn(&Expr) -> Option<T>,
+{
+    // Determine the dimensions of the array from its first element at each level.
+    let mut dimensions: Vec<usize> = Vec::new();
+    let mut expr: &Expr = array;
+
+    while let Some(elements) = list_elements(expr) {
+        dimensions.push(elements.len());
+
+        match elements.first() {
+            Some(first) => expr = first,
+            None => break,
+        }
+    }
+
+    let mut data: Vec<T> = Vec::with_capacity(dimensions.iter().product());
+
+    // Flatten the array, checking that it is rectangular.
+    let mut stack: Vec<(&Expr, usize)> = vec![(array, 0)];
+
+    while let Some((expr, level)) = stack.pop() {
+        if level < dimensions.len() {
+            match list_elements(expr) {
+                Some(elements) if elements.len() == dimensions[level] => {
+                    stack.extend(elements.iter().rev().map(|elem| (elem, level + 1)))
+                },
+                _ => {
+                    return Err(Error::custom(format!(
+                        "{}: array is not rectangular: {}",
+                        func_name, array
+                    )))
+                },
+            }
+        } else {
+            data.push(convert(expr).ok_or_else(|| {
+                Error::custom(format!(
+                    "{}: array element is not {}: {}",
+                    func_name, element_description, expr
+                ))
+            })?);
+        }
+    }
+
+    Ok((data, dimensions))
+}
+
+/// Convert a two-dimensional real `SparseArray[...]` expression into its nonzero
+/// indices, values, and dimensions, as described by [`Link::get_sparse_array()`].
+#[allow(clippy::type_complexity)]
+fn sparse_array_from_expr(
+    expr: &Expr,
+) -> Option<(Vec<(usize, usize)>, Vec<f64>, (usize, usize))> {
+    fn index_from_expr(expr: &Expr) -> Option<usize> {
+        match expr.kind() {
+            ExprKind::Integer(int) => usize::try_from(*int).ok(),
+            _ => None,
+        }
+    }
+
+    fn real_from_expr(expr: &Expr) -> Option<f64> {
+        let (re, im) = complex_from_expr(expr)?;
+
+        if im != 0.0 {
+            return None;
+        }
+
+        Some(re)
+    }
+
+    fn dims_from_expr(expr: &Expr) -> Option<(usize, usize)> {
+        match list_elements(expr)? {
+            [rows, columns] => Some((index_from_expr(rows)?, index_from_expr(columns)?)),
+            _ => None,
+        }
+    }
+
+    let elements: &[Expr] = match expr.kind() {
+        ExprKind::Normal(normal)
+            if normal.has_head(&Symbol::new("System`SparseArray")) =>
+        {
+            normal.elements()
+        },
+        _ => return None,
+    };
+
+    let mut indices: Vec<(usize, usize)> = Vec::new();
+    let mut values: Vec<f64> = Vec::new();
+
+    match elements {
+        // SparseArray[Automatic, {m, n}, default,
+        //             {1, {rowPointers, columnIndices}, values}]
+        [automatic, dims, default, data]
+            if *automatic == Expr::symbol(Symbol::new("System`Automatic")) =>
+        {
+            let (rows, columns) = dims_from_expr(dims)?;
+
+            if real_from_expr(default)? != 0.0 {
+                return None;
+            }
+
+            let (structure, data_values) = match list_elements(data)? {
+                [_version, structure, data_values] => (structure, data_values),
+                _ => return None,
+            };
+
+            let (row_pointers, column_indices) = match list_elements(structure)? {
+                [row_pointers, column_indices] => {
+                    (list_elements(row_pointers)?, list_elements(column_indices)?)
+                },
+                _ => return None,
+            };
+
+            let data_values = list_elements(data_values)?;
+
+            if row_pointers.len() != rows + 1 || column_indices.len() != data_values.len()
+            {
+                return None;
+            }
+
+            for row in 0..rows {
+                let start = index_from_expr(&row_pointers[row])?;
+                let end = index_from_expr(&row_pointers[row + 1])?;
+
+                for offset in start..end {
+                    // Each column index is a one-element list containing a one-based
+                    // index.
+                    let column = match list_elements(column_indices.get(offset)?)? {
+                        [column] => index_from_expr(column)?.checked_sub(1)?,
+                        _ => return None,
+                    };
+
+                    if column >= columns {
+                        return None;
+                    }
+
+                    indices.push((row, column));
+                    values.push(real_from_expr(data_values.get(offset)?)?);
+                }
+            }
+
+            Some((indices, values, (rows, columns)))
+        },
+        // SparseArray[{{i, j} -> value, ...}, {m, n}] or
+        // SparseArray[{{i, j} -> value, ...}, {m, n}, default]
+        [rules, dims] | [rules, dims, _] => {
+            if let [_, _, default] = elements {
+                if real_from_expr(default)? != 0.0 {
+                    return None;
+                }
+            }
+
+            let (rows, columns) = dims_from_expr(dims)?;
+
+            for rule in list_elements(rules)? {
+                let (index, value) = match rule.kind() {
+                    ExprKind::Normal(normal)
+                        if normal.has_head(&Symbol::new("System`Rule")) =>
+                    {
+                        match normal.elements() {
+                            [index, value] => (index, value),
+                            _ => return None,
+                        }
+                    },
+                    _ => return None,
+                };
+
+                let (row, column) = dims_from_expr(index)?;
+                let (row, column) = (row.checked_sub(1)?, column.checked_sub(1)?);
+
+                if row >= rows || column >= columns {
+                    return None;
+                }
+
+                indices.push((row, column));
+                values.push(real_from_expr(value)?);
+            }
+
+            Some((indices, values, (rows, columns)))
+        },
+        _ => None,
+    }
+}
+
+/// Convert a `Complex[re, im]`, real, or integer expression into a `(re, im)` pair.
+fn complex_from_expr(expr: &Expr) -> Option<(f64, f64)> {
+    fn real_from_expr(expr: &Expr) -> Option<f64> {
+        match expr.kind() {
+            ExprKind::Integer(int) => Some(*int as f64),
+            ExprKind::Real(real) => Some(**real),
+            _ => None,
+        }
+    }
+
+    match expr.kind() {
+        ExprKind::Normal(normal) if normal.has_head(&Symbol::new("System`Complex")) => {
+            match normal.elements() {
+                [re, im] => Some((real_from_expr(re)?, real_from_expr(im)?)),
+                _ => None,
+            }
+        },
+        _ => real_from_expr(expr).map(|re| (re, 0.0)),
+    }
+}
+
 impl<'link, T: LinkStrType + ?Sized> LinkStr<'link, T> {
     /// Get the string contained by this `LinkStr`.
     pub fn get<'this>(&'this self) -> &'this T {
@@ -795,6 +2775,25 @@ unsafe impl LinkStrType for Utf32Str {
 /// * [`Link::get_u8_array()`]
 /// * [`Link::get_f64_array()`]
 /// * [`Link::get_f32_array()`]
+///
+/// and the corresponding `*_exact()` functions, like [`Link::get_i64_array_exact()`].
+///
+/// # Element type conversions
+///
+/// The non-exact array functions will convert the elements of the incoming array to
+/// the requested type if necessary:
+///
+/// | Incoming elements | Integer array functions        | Real array functions          |
+/// |-------------------|--------------------------------|-------------------------------|
+/// | Integers          | Not converted[^range]          | Converted to the nearest real |
+/// | Reals             | Rounded to the nearest integer | Not converted[^f32]           |
+///
+/// [^range]: Integers outside the range of the requested type are not checked for.
+/// [^f32]: Reals read using [`Link::get_f32_array()`] may lose precision.
+///
+/// The `*_exact()` functions perform no conversions, and return an error instead if
+/// any element of the incoming array is not exactly representable as the requested
+/// type.
 pub struct Array<'link, T> {
     link: &'link Link,
 
@@ -804,6 +2803,45 @@ pub struct Array<'link, T> {
     dimensions: Vec<usize>,
 }
 
+impl NumericArray {
+    /// Access the elements of this array as a flat buffer.
+    pub fn data(&self) -> &NumericArrayData {
+        &self.data
+    }
+
+    /// Consume this array, returning its elements.
+    pub fn into_data(self) -> NumericArrayData {
+        self.data
+    }
+
+    /// Get the dimensions of this array.
+    pub fn dimensions(&self) -> &[usize] {
+        self.dimensions.as_slice()
+    }
+
+    /// Get the number of dimensions in this array.
+    pub fn rank(&self) -> usize {
+        self.dimensions.len()
+    }
+
+    /// Get the Wolfram Language name of the element type of this array, e.g.
+    /// `"Integer8"`.
+    pub fn type_name(&self) -> &'static str {
+        match self.data {
+            NumericArrayData::Integer8(_) => "Integer8",
+            NumericArrayData::Integer16(_) => "Integer16",
+            NumericArrayData::Integer32(_) => "Integer32",
+            NumericArrayData::Integer64(_) => "Integer64",
+            NumericArrayData::UnsignedInteger8(_) => "UnsignedInteger8",
+            NumericArrayData::UnsignedInteger16(_) => "UnsignedInteger16",
+            NumericArrayData::UnsignedInteger32(_) => "UnsignedInteger32",
+            NumericArrayData::UnsignedInteger64(_) => "UnsignedInteger64",
+            NumericArrayData::Real32(_) => "Real32",
+            NumericArrayData::Real64(_) => "Real64",
+        }
+    }
+}
+
 impl<'link, T> Array<'link, T> {
     /// Access the elements stored in this [`Array`] as a flat buffer.
     pub fn data<'s>(&'s self) -> &'s [T] {
@@ -834,6 +2872,100 @@ impl<'link, T> Array<'link, T> {
     pub fn length(&self) -> usize {
         self.dimensions[0]
     }
+
+    /// Compute the offset into [`Array::data()`] of the element at `index`, using
+    /// row-major order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` does not have one element per dimension, or if any element of
+    /// `index` is out of bounds for its dimension.
+    fn flat_index(&self, index: &[usize]) -> usize {
+        assert_eq!(
+            index.len(),
+            self.rank(),
+            "array index has {} elements, but array has rank {}",
+            index.len(),
+            self.rank()
+        );
+
+        index
+            .iter()
+            .zip(&self.dimensions)
+            .fold(0, |offset, (&i, &dim)| {
+                assert!(
+                    i < dim,
+                    "array index out of bounds: the dimension length is {} but the index \
+                     is {}",
+                    dim,
+                    i
+                );
+
+                offset * dim + i
+            })
+    }
+}
+
+/// Iterate over the elements of the array in row-major order, like
+/// [`Array::data()`].
+impl<'a, 'link, T> IntoIterator for &'a Array<'link, T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data().iter()
+    }
+}
+
+/// Index into a rank-1 array.
+///
+/// # Panics
+///
+/// Panics if the array does not have rank 1, or if `index` is out of bounds.
+impl<'link, T> Index<usize> for Array<'link, T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self[&[index]]
+    }
+}
+
+/// Index into an array using one index per dimension, e.g. `array[&[i, j]]`.
+///
+/// # Panics
+///
+/// Panics if `index` does not have one element per dimension of the array, or if any
+/// element of `index` is out of bounds.
+///
+/// # Example
+///
+/// ```
+/// use wstp::Link;
+///
+/// let mut link = Link::new_loopback().unwrap();
+///
+/// link.put_i64_array(&[1, 2, 3, 4, 5, 6], &[2, 3]).unwrap();
+///
+/// let array = link.get_i64_array().unwrap();
+///
+/// assert_eq!(array[&[1, 0]], 4);
+/// assert_eq!(array[&[0, 2][..]], 3);
+/// assert_eq!((&array).into_iter().sum::<i64>(), 21);
+/// ```
+impl<'link, T> Index<&[usize]> for Array<'link, T> {
+    type Output = T;
+
+    fn index(&self, index: &[usize]) -> &T {
+        &self.data()[self.flat_index(index)]
+    }
+}
+
+impl<'link, T, const N: usize> Index<&[usize; N]> for Array<'link, T> {
+    type Output = T;
+
+    fn index(&self, index: &[usize; N]) -> &T {
+        &self[&index[..]]
+    }
 }
 
 impl<'link, T> Drop for Array<'link, T> {
@@ -0,0 +1,183 @@
+//! Configuration of how a Wolfram Kernel process is launched.
+
+use std::{path::PathBuf, process, time::Duration};
+
+use crate::{Link, Protocol};
+
+use super::{Error, WolframKernelProcess};
+
+/// Options controlling how a Wolfram Kernel process is launched.
+///
+/// Use [`LaunchOptions::new()`] to construct the default options for a
+/// [`WolframKernel`][WolframKernel] executable, configure them using the builder
+/// methods, and then call [`LaunchOptions::launch()`].
+///
+/// [WolframKernel]: https://reference.wolfram.com/language/ref/program/WolframKernel.html
+///
+/// # Example
+///
+/// ```no_run
+/// use std::{path::PathBuf, time::Duration};
+/// use wstp::{kernel::LaunchOptions, Protocol};
+///
+/// let exe = PathBuf::from("/usr/local/bin/WolframKernel");
+///
+/// let kernel = LaunchOptions::new(exe)
+///     .protocol(Protocol::TCPIP)
+///     .no_init(true)
+///     .connect_timeout(Duration::from_secs(30))
+///     .launch()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchOptions {
+    executable: PathBuf,
+    protocol: Protocol,
+    initialization_file: Option<PathBuf>,
+    no_init: bool,
+    extra_args: Vec<String>,
+    connect_timeout: Option<Duration>,
+}
+
+impl LaunchOptions {
+    /// Construct the default options for launching the `WolframKernel` executable at
+    /// `executable`.
+    ///
+    /// By default, the kernel is connected using a [`SharedMemory`][Protocol::SharedMemory]
+    /// link, with no launch timeout.
+    pub fn new(executable: PathBuf) -> Self {
+        LaunchOptions {
+            executable,
+            protocol: Protocol::SharedMemory,
+            initialization_file: None,
+            no_init: false,
+            extra_args: Vec::new(),
+            connect_timeout: None,
+        }
+    }
+
+    /// Set the path of the `WolframKernel` executable to launch.
+    pub fn executable(self, executable: PathBuf) -> Self {
+        LaunchOptions { executable, ..self }
+    }
+
+    /// Set the protocol of the link used to communicate with the kernel.
+    ///
+    /// [`IntraProcess`][Protocol::IntraProcess] links cannot be used to communicate
+    /// with another process, and will cause [`LaunchOptions::launch()`] to return an
+    /// error.
+    pub fn protocol(self, protocol: Protocol) -> Self {
+        LaunchOptions { protocol, ..self }
+    }
+
+    /// Set a file for the kernel to read on startup, using the `-initfile` command-line
+    /// option.
+    pub fn initialization_file(self, path: PathBuf) -> Self {
+        LaunchOptions {
+            initialization_file: Some(path),
+            ..self
+        }
+    }
+
+    /// If `true`, prevent the kernel from reading any initialization files on startup,
+    /// using the `-noinit` command-line option.
+    pub fn no_init(self, no_init: bool) -> Self {
+        LaunchOptions { no_init, ..self }
+    }
+
+    /// Set additional command-line arguments to pass to the kernel.
+    ///
+    /// These are passed after the arguments used to connect the kernel to the link.
+    pub fn extra_args(self, extra_args: Vec<String>) -> Self {
+        LaunchOptions { extra_args, ..self }
+    }
+
+    /// Set the maximum amount of time to wait for the launched kernel to connect.
+    ///
+    /// If the kernel has not connected once `timeout` has elapsed, the kernel process
+    /// is killed and an error is returned, for which [`Error::is_timeout()`] returns
+    /// `true`. By default, there is no timeout.
+    pub fn connect_timeout(self, timeout: Duration) -> Self {
+        LaunchOptions {
+            connect_timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Launch a new Wolfram Kernel child process using these options, and establish a
+    /// WSTP connection with it.
+    pub fn launch(&self) -> Result<WolframKernelProcess, Error> {
+        if self.protocol == Protocol::IntraProcess {
+            return Err(Error::new(
+                "IntraProcess links cannot be used to communicate with a kernel process"
+                    .to_owned(),
+            ));
+        }
+
+        let link = Link::listen(self.protocol.clone(), "")?;
+
+        self.launch_with_link(link)
+    }
+
+    /// Launch a kernel process that will connect to the listening `link`, and wait for
+    /// it to connect.
+    pub(super) fn launch_with_link(
+        &self,
+        mut link: Link,
+    ) -> Result<WolframKernelProcess, Error> {
+        let LaunchOptions {
+            executable,
+            protocol,
+            initialization_file,
+            no_init,
+            extra_args,
+            connect_timeout,
+        } = self;
+
+        let name = link.link_name();
+        assert!(!name.is_empty());
+
+        let mut command = process::Command::new(executable);
+
+        command
+            .arg("-wstp")
+            .arg("-linkprotocol")
+            .arg(protocol.to_string())
+            .arg("-linkconnect")
+            .arg("-linkname")
+            .arg(&name);
+
+        if *no_init {
+            command.arg("-noinit");
+        }
+
+        if let Some(initialization_file) = initialization_file {
+            command.arg("-initfile").arg(initialization_file);
+        }
+
+        let mut kernel_process = command.args(extra_args).spawn()?;
+
+        // Wait for an incoming connection to be made to the listening link.
+        // This will block until a connection is made, or the timeout elapses.
+        //
+        // FIXME: Without a timeout, if the spawned process fails to connect for some
+        //        reason (e.g. a launched Kernel doesn't start due to a licensing
+        //        error), this will just wait forever, hanging the current program.
+        let result = match connect_timeout {
+            Some(timeout) => link.activate_timeout(*timeout),
+            None => link.activate(),
+        };
+
+        if let Err(err) = result {
+            let _ = kernel_process.kill();
+            let _ = kernel_process.wait();
+
+            return Err(Error::from(err));
+        }
+
+        Ok(WolframKernelProcess {
+            process: kernel_process,
+            link,
+        })
+    }
+}
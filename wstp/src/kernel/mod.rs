@@ -38,6 +38,42 @@
 //! different computers, without relying on end-user configuration to specify the location
 //! of the local Wolfram Language installation.
 //!
+//! # Evaluating expressions
+//!
+//! The result of evaluating an [`EvaluatePacket`][EvaluatePacket] is sent back by the
+//! kernel wrapped in a [`ReturnPacket`][ReturnPacket], and may be preceded by other
+//! packets, like the `InputNamePacket` a newly launched kernel sends. Reading the
+//! response using [`Link::get_expr()`] returns the first of those packets, not the
+//! result. Use [`Link::get_return_value()`] to skip to the `ReturnPacket` and unwrap
+//! the result:
+//!
+//! ```no_run
+//! use std::path::PathBuf;
+//! use wolfram_expr::{Expr, Symbol};
+//! use wstp::kernel::WolframKernelProcess;
+//!
+//! let exe = PathBuf::from(
+//!     "/Applications/Mathematica.app/Contents/MacOS/WolframKernel"
+//! );
+//!
+//! let mut kernel = WolframKernelProcess::launch(&exe).unwrap();
+//!
+//! let link = kernel.link();
+//!
+//! let plus = Expr::normal(
+//!     Symbol::new("System`Plus"),
+//!     vec![Expr::from(1), Expr::from(1)],
+//! );
+//!
+//! link.put_eval_packet(&plus).unwrap();
+//! link.flush().unwrap();
+//!
+//! assert_eq!(link.get_return_value().unwrap(), Expr::from(2));
+//! ```
+//!
+//! [EvaluatePacket]: https://reference.wolfram.com/language/ref/EvaluatePacket.html
+//! [ReturnPacket]: https://reference.wolfram.com/language/ref/ReturnPacket.html
+//!
 //!
 //! [WolframKernel]: https://reference.wolfram.com/language/ref/program/WolframKernel.html
 //! [wolfram-app-discovery]: https://crates.io/crates/wolfram-app-discovery
@@ -55,39 +91,72 @@
 //! #### Link packet methods
 //!
 //! * [`Link::put_eval_packet()`]
+//! * [`Link::put_enter_text_packet()`]
+//! * [`Link::next_packet()`]
+//! * [`Link::get_return_value()`]
 
-use std::{path::PathBuf, process};
+mod launch;
+mod pool;
+
+use std::{net::ToSocketAddrs, path::PathBuf, process};
 
 use wolfram_expr::Expr;
 
-use crate::{Error as WstpError, Link, Protocol};
+use crate::{sys, Error as WstpError, Link, Protocol};
+
+pub use self::{
+    launch::LaunchOptions,
+    pool::{KernelPool, PooledKernel},
+};
 
 /// Handle to a Wolfram Kernel process connected via WSTP.
 ///
-/// Use [`WolframKernelProcess::launch()`] to launch a new Wolfram Kernel process.
+/// Use [`WolframKernelProcess::launch()`] to launch a new Wolfram Kernel process, or
+/// [`LaunchOptions`] to configure how the process is launched.
 ///
 /// Use [`WolframKernelProcess::link()`] to access the WSTP [`Link`] used to communicate with
 /// this kernel.
 #[derive(Debug)]
 pub struct WolframKernelProcess {
-    #[allow(dead_code)]
     process: process::Child,
     link: Link,
 }
 
 /// Wolfram Kernel process error.
 #[derive(Debug)]
-pub struct Error(String);
+pub struct Error {
+    message: String,
+    /// See [`Error::is_timeout()`].
+    timed_out: bool,
+}
+
+impl Error {
+    fn new(message: String) -> Error {
+        Error {
+            message,
+            timed_out: false,
+        }
+    }
+
+    /// Returns `true` if this error was caused by the kernel not connecting before the
+    /// timeout set using [`LaunchOptions::connect_timeout()`] elapsed.
+    pub fn is_timeout(&self) -> bool {
+        self.timed_out
+    }
+}
 
 impl From<WstpError> for Error {
     fn from(err: WstpError) -> Error {
-        Error(format!("WSTP error: {err}"))
+        Error {
+            message: format!("WSTP error: {err}"),
+            timed_out: err.is_timeout(),
+        }
     }
 }
 
 impl From<std::io::Error> for Error {
     fn from(err: std::io::Error) -> Error {
-        Error(format!("IO error: {err}"))
+        Error::new(format!("IO error: {err}"))
     }
 }
 
@@ -105,36 +174,39 @@ impl WolframKernelProcess {
     // TODO: Would it be correct to describe this as essentially `LinkLaunch`? Also note
     //       that this doesn't actually use `-linkmode launch`.
     pub fn launch(path: &PathBuf) -> Result<WolframKernelProcess, Error> {
-        let mut link = Link::listen(Protocol::SharedMemory, "")?;
-
-        let name = link.link_name();
-        assert!(!name.is_empty());
-
-        let kernel_process = process::Command::new(path)
-            .arg("-wstp")
-            .arg("-linkprotocol")
-            .arg("SharedMemory")
-            .arg("-linkconnect")
-            .arg("-linkname")
-            .arg(&name)
-            .spawn()?;
-
-        // Wait for an incoming connection to be made to the listening link.
-        // This will block until a connection is made.
-        //
-        // FIXME: This currently has an infinite timeout. If the spawned
-        //        process fails to connect for some reason (e.g. a launched
-        //        Kernel doesn't start due to a licensing error), this will
-        //        just wait forever, hanging the current program.
-        //
-        //        TODO: Set a yield function that will abort if a timeout
-        //              duration is reached.
-        let () = link.activate()?;
-
-        Ok(WolframKernelProcess {
-            process: kernel_process,
-            link,
-        })
+        LaunchOptions::new(path.clone()).launch()
+    }
+
+    /// Launch a new Wolfram Kernel child process and establish a WSTP connection with it
+    /// over a [`TCPIP`][Protocol::TCPIP] link bound to `bind_addr`.
+    ///
+    /// This is equivalent to [`WolframKernelProcess::launch()`], except that the
+    /// kernel connects over TCP/IP instead of shared memory. This is useful when the
+    /// kernel runs in a different container or namespace than the current process, so
+    /// that shared memory is not shared between them.
+    ///
+    /// The kernel connects to the link name reported by the listening link, so
+    /// `bind_addr` must be an address the kernel is able to reach.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use wstp::kernel::WolframKernelProcess;
+    ///
+    /// let exe = PathBuf::from("/usr/local/bin/WolframKernel");
+    ///
+    /// let kernel = WolframKernelProcess::launch_tcpip(&exe, "127.0.0.1:8080").unwrap();
+    /// ```
+    pub fn launch_tcpip<A: ToSocketAddrs>(
+        path: &PathBuf,
+        bind_addr: A,
+    ) -> Result<WolframKernelProcess, Error> {
+        let link = Link::tcpip_listen(bind_addr)?;
+
+        LaunchOptions::new(path.clone())
+            .protocol(Protocol::TCPIP)
+            .launch_with_link(link)
     }
 
     /// Get the WSTP [`Link`] connection used to communicate with this Wolfram Kernel
@@ -145,6 +217,83 @@ impl WolframKernelProcess {
     }
 }
 
+/// Type of a packet sent between a Wolfram Kernel and its controlling program.
+///
+/// Returned by [`Link::next_packet()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PacketType {
+    /// [`InputNamePacket`](https://reference.wolfram.com/language/ref/InputNamePacket.html)
+    InputName,
+    /// [`OutputNamePacket`](https://reference.wolfram.com/language/ref/OutputNamePacket.html)
+    OutputName,
+    /// [`ReturnPacket`](https://reference.wolfram.com/language/ref/ReturnPacket.html)
+    Return,
+    /// [`ReturnTextPacket`](https://reference.wolfram.com/language/ref/ReturnTextPacket.html)
+    ReturnText,
+    /// [`ReturnExpressionPacket`](https://reference.wolfram.com/language/ref/ReturnExpressionPacket.html)
+    ReturnExpression,
+    /// [`TextPacket`](https://reference.wolfram.com/language/ref/TextPacket.html)
+    Text,
+    /// [`MessagePacket`](https://reference.wolfram.com/language/ref/MessagePacket.html)
+    Message,
+    /// [`MenuPacket`](https://reference.wolfram.com/language/ref/MenuPacket.html)
+    Menu,
+    /// [`SyntaxPacket`](https://reference.wolfram.com/language/ref/SyntaxPacket.html)
+    Syntax,
+    /// [`InputPacket`](https://reference.wolfram.com/language/ref/InputPacket.html)
+    Input,
+    /// [`InputStringPacket`](https://reference.wolfram.com/language/ref/InputStringPacket.html)
+    InputString,
+    /// [`DisplayPacket`](https://reference.wolfram.com/language/ref/DisplayPacket.html)
+    Display,
+    /// [`DisplayEndPacket`](https://reference.wolfram.com/language/ref/DisplayEndPacket.html)
+    DisplayEnd,
+    /// [`BeginDialogPacket`](https://reference.wolfram.com/language/ref/BeginDialogPacket.html)
+    BeginDialog,
+    /// [`EndDialogPacket`](https://reference.wolfram.com/language/ref/EndDialogPacket.html)
+    EndDialog,
+    /// [`CallPacket`](https://reference.wolfram.com/language/ref/CallPacket.html)
+    Call,
+    /// [`EvaluatePacket`](https://reference.wolfram.com/language/ref/EvaluatePacket.html)
+    Evaluate,
+    /// [`EnterTextPacket`](https://reference.wolfram.com/language/ref/EnterTextPacket.html)
+    EnterText,
+    /// [`EnterExpressionPacket`](https://reference.wolfram.com/language/ref/EnterExpressionPacket.html)
+    EnterExpression,
+    /// Any other packet type, identified by its raw WSTP packet code.
+    Other(i32),
+}
+
+impl PacketType {
+    /// Get the `PacketType` corresponding to a raw WSTP packet code, like
+    /// [`sys::RETURNPKT`][crate::sys::RETURNPKT].
+    pub fn from_raw(code: i32) -> PacketType {
+        match code {
+            sys::INPUTNAMEPKT => PacketType::InputName,
+            sys::OUTPUTNAMEPKT => PacketType::OutputName,
+            sys::RETURNPKT => PacketType::Return,
+            sys::RETURNTEXTPKT => PacketType::ReturnText,
+            sys::RETURNEXPRPKT => PacketType::ReturnExpression,
+            sys::TEXTPKT => PacketType::Text,
+            sys::MESSAGEPKT => PacketType::Message,
+            sys::MENUPKT => PacketType::Menu,
+            sys::SYNTAXPKT => PacketType::Syntax,
+            sys::INPUTPKT => PacketType::Input,
+            sys::INPUTSTRPKT => PacketType::InputString,
+            sys::DISPLAYPKT => PacketType::Display,
+            sys::DISPLAYENDPKT => PacketType::DisplayEnd,
+            sys::BEGINDLGPKT => PacketType::BeginDialog,
+            sys::ENDDLGPKT => PacketType::EndDialog,
+            sys::CALLPKT => PacketType::Call,
+            sys::EVALUATEPKT => PacketType::Evaluate,
+            sys::ENTERTEXTPKT => PacketType::EnterText,
+            sys::ENTEREXPRPKT => PacketType::EnterExpression,
+            other => PacketType::Other(other),
+        }
+    }
+}
+
 impl Link {
     /// Put an [`EvaluatePacket[expr]`][EvaluatePacket] onto the link.
     ///
@@ -156,4 +305,84 @@ impl Link {
 
         Ok(())
     }
+
+    /// Put an [`EnterTextPacket["code"]`][EnterTextPacket] onto the link.
+    ///
+    /// Unlike [`Link::put_eval_packet()`], this evaluates `code` as if it had been
+    /// entered at a text-based kernel prompt: the input and output are recorded as
+    /// `In[n]` and `Out[n]`, and the result is sent back as text formatted for display.
+    ///
+    /// # Response packets
+    ///
+    /// A kernel typically responds to an `EnterTextPacket` by sending, in order:
+    ///
+    /// 1. Any [`Message`][PacketType::Message] and [`Text`][PacketType::Text] packets
+    ///    for messages and `Print[..]` output produced by the evaluation.
+    /// 2. If the result is not `Null`, an [`OutputName`][PacketType::OutputName] packet
+    ///    (`OutputNamePacket["Out[n]= "]`), followed by a
+    ///    [`ReturnText`][PacketType::ReturnText] packet containing the result.
+    /// 3. An [`InputName`][PacketType::InputName] packet
+    ///    (`InputNamePacket["In[n+1]:= "]`), indicating that the kernel is ready for the
+    ///    next input.
+    ///
+    /// Use [`Link::next_packet()`] to determine the type of each packet, and
+    /// [`Link::new_packet()`] to skip packets that are not needed.
+    ///
+    /// [EnterTextPacket]: https://reference.wolfram.com/language/ref/EnterTextPacket.html
+    pub fn put_enter_text_packet(&mut self, code: &str) -> Result<(), Error> {
+        self.put_function("System`EnterTextPacket", 1)?;
+        self.put_str(code)?;
+        self.end_packet()?;
+
+        Ok(())
+    }
+
+    /// Advance to the start of the next packet on this link, and return its type.
+    ///
+    /// After this returns, the contents of the packet can be read, e.g. using
+    /// [`Link::get_string()`] for an [`OutputName`][PacketType::OutputName] packet.
+    ///
+    /// *WSTP C API Documentation:* [`WSNextPacket()`](https://reference.wolfram.com/language/ref/c/WSNextPacket.html)
+    pub fn next_packet(&mut self) -> Result<PacketType, Error> {
+        let code = self.raw_next_packet()?;
+
+        Ok(PacketType::from_raw(code))
+    }
+
+    /// Read the result of an evaluation, unwrapping it from the
+    /// [`ReturnPacket`](https://reference.wolfram.com/language/ref/ReturnPacket.html)
+    /// it is sent in.
+    ///
+    /// Packets that only carry information about the evaluation, and that may be sent
+    /// before the `ReturnPacket`, are skipped. These are
+    /// [`InputName`][PacketType::InputName], [`OutputName`][PacketType::OutputName],
+    /// [`Message`][PacketType::Message], and [`Text`][PacketType::Text] packets. An
+    /// error is returned if any other type of packet is received instead of a
+    /// `ReturnPacket`, e.g. a [`ReturnText`][PacketType::ReturnText] packet sent in
+    /// response to [`Link::put_enter_text_packet()`]. That packet is skipped.
+    ///
+    /// Symbols in the result that were sent without a context, as the kernel does for
+    /// ``System` `` symbols like `List`, are resolved in the ``System` `` context.
+    ///
+    /// See the [module documentation][crate::kernel#evaluating-expressions] for an
+    /// example.
+    pub fn get_return_value(&mut self) -> Result<Expr, Error> {
+        loop {
+            match self.next_packet()? {
+                PacketType::Return => return Ok(self.get_system_expr()?),
+                PacketType::InputName
+                | PacketType::OutputName
+                | PacketType::Message
+                | PacketType::Text => self.new_packet()?,
+                other => {
+                    self.new_packet()?;
+
+                    return Err(Error::new(format!(
+                        "expected ReturnPacket, got packet of type {:?}",
+                        other
+                    )));
+                },
+            }
+        }
+    }
 }
@@ -0,0 +1,291 @@
+//! A pool of Wolfram Kernel processes that can be shared between threads.
+
+use std::{
+    ops::{Deref, DerefMut},
+    path::PathBuf,
+    sync::{Condvar, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use wolfram_expr::{Expr, Symbol};
+
+use super::{Error, PacketType, WolframKernelProcess};
+
+/// Maximum amount of time a kernel has to respond to the health check evaluation before
+/// it is considered unresponsive and is replaced.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A fixed-size pool of Wolfram Kernel processes.
+///
+/// Use [`KernelPool::acquire()`] to get exclusive access to one of the kernels in the
+/// pool. The kernel is returned to the pool when the [`PooledKernel`] guard is dropped.
+///
+/// Each time a kernel is acquired, it is health checked by evaluating `1 + 1`. Kernels
+/// that have exited, or that do not respond with `2`, are closed and replaced by a newly
+/// launched kernel.
+///
+/// `KernelPool` is [`Sync`], so it can be shared between threads, e.g. by wrapping it in
+/// an [`Arc`][std::sync::Arc].
+///
+/// The kernel processes are terminated when the pool is dropped.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::path::PathBuf;
+/// use wstp::kernel::KernelPool;
+///
+/// let exe = PathBuf::from(
+///     "/Applications/Mathematica.app/Contents/MacOS/WolframKernel"
+/// );
+///
+/// let pool = KernelPool::new(&exe, 4).unwrap();
+///
+/// let mut kernel = pool.acquire().unwrap();
+///
+/// kernel.link().put_eval_packet(&wolfram_expr::Expr::from(5)).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct KernelPool {
+    path: PathBuf,
+    size: usize,
+    /// Kernels that are not currently acquired. `None` entries are slots whose kernel
+    /// needs to be (re)launched the next time they are acquired.
+    idle: Mutex<Vec<Option<WolframKernelProcess>>>,
+    /// Notified each time a slot is returned to `idle`.
+    returned: Condvar,
+}
+
+/// Guard providing exclusive access to a kernel acquired from a [`KernelPool`].
+///
+/// The kernel is returned to the pool when this guard is dropped. Any packets that have
+/// been received from the kernel but not read are discarded at that point. Results of
+/// evaluations that are still in progress are not waited for; if one is received later,
+/// the kernel will likely fail its next health check and be replaced.
+#[derive(Debug)]
+pub struct PooledKernel<'p> {
+    pool: &'p KernelPool,
+    /// Always `Some`, until the kernel is returned to `pool` in `Drop`.
+    kernel: Option<WolframKernelProcess>,
+}
+
+impl KernelPool {
+    /// Launch `size` Wolfram Kernel processes from the `WolframKernel` executable at
+    /// `path`, and return a pool managing them.
+    ///
+    /// An error is returned if `size` is 0, or if launching any of the kernels fails. In
+    /// the latter case, the kernels that were already launched are terminated.
+    ///
+    /// See [`WolframKernelProcess::launch()`].
+    pub fn new(path: &PathBuf, size: usize) -> Result<KernelPool, Error> {
+        if size == 0 {
+            return Err(Error::new(
+                "KernelPool::new(): pool size must be at least 1".to_owned(),
+            ));
+        }
+
+        let mut idle = Vec::with_capacity(size);
+
+        for _ in 0..size {
+            match WolframKernelProcess::launch(path) {
+                Ok(kernel) => idle.push(Some(kernel)),
+                Err(err) => {
+                    // Don't leave the kernels that were already launched running.
+                    for kernel in idle.into_iter().flatten() {
+                        kernel.kill();
+                    }
+                    return Err(err);
+                },
+            }
+        }
+
+        Ok(KernelPool {
+            path: path.clone(),
+            size,
+            idle: Mutex::new(idle),
+            returned: Condvar::new(),
+        })
+    }
+
+    /// Get the number of kernels managed by this pool.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Acquire a kernel from this pool, blocking until one is available.
+    ///
+    /// The kernel is health checked before it is returned. If it has died or does not
+    /// respond correctly, it is replaced by a newly launched kernel. An error is
+    /// returned if launching the replacement kernel fails.
+    pub fn acquire(&self) -> Result<PooledKernel<'_>, Error> {
+        let slot: Option<WolframKernelProcess> = {
+            let mut idle = self.lock_idle();
+
+            loop {
+                match idle.pop() {
+                    Some(slot) => break slot,
+                    None => {
+                        idle = self
+                            .returned
+                            .wait(idle)
+                            .expect("failed to wait on KernelPool condition variable")
+                    },
+                }
+            }
+        };
+
+        let kernel = match slot {
+            Some(mut kernel) => {
+                if kernel.is_healthy() {
+                    Some(kernel)
+                } else {
+                    kernel.kill();
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let kernel = match kernel {
+            Some(kernel) => kernel,
+            None => match WolframKernelProcess::launch(&self.path) {
+                Ok(kernel) => kernel,
+                Err(err) => {
+                    // Return the empty slot, so that a later call can try to launch a
+                    // kernel again.
+                    self.return_slot(None);
+                    return Err(err);
+                },
+            },
+        };
+
+        Ok(PooledKernel {
+            pool: self,
+            kernel: Some(kernel),
+        })
+    }
+
+    fn lock_idle(&self) -> MutexGuard<'_, Vec<Option<WolframKernelProcess>>> {
+        self.idle
+            .lock()
+            .expect("failed to acquire lock on KernelPool kernels")
+    }
+
+    fn return_slot(&self, slot: Option<WolframKernelProcess>) {
+        self.lock_idle().push(slot);
+        self.returned.notify_one();
+    }
+}
+
+impl Drop for KernelPool {
+    fn drop(&mut self) {
+        // All `PooledKernel` guards borrow the pool, so every kernel is idle by now.
+        let idle = match self.idle.get_mut() {
+            Ok(idle) => idle,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        for kernel in idle.drain(..).flatten() {
+            kernel.kill();
+        }
+    }
+}
+
+impl WolframKernelProcess {
+    /// Check that this kernel process is still running, and evaluates `1 + 1` to `2`.
+    ///
+    /// Any packets sent by the kernel before the `ReturnPacket` of the evaluation (e.g.
+    /// `InputNamePacket`) are discarded.
+    ///
+    /// The packets are read without calling [`Link::get_expr()`][crate::Link::get_expr],
+    /// because the kernel sends symbols like `ReturnPacket` without a context.
+    fn is_healthy(&mut self) -> bool {
+        if !matches!(self.process.try_wait(), Ok(None)) {
+            return false;
+        }
+
+        let link = self.link();
+
+        let plus = Expr::normal(
+            Symbol::new("System`Plus"),
+            vec![Expr::from(1), Expr::from(1)],
+        );
+
+        if link.put_eval_packet(&plus).is_err() || link.flush().is_err() {
+            return false;
+        }
+
+        loop {
+            if !matches!(link.wait_ready(HEALTH_CHECK_TIMEOUT), Ok(true)) {
+                return false;
+            }
+
+            match link.next_packet() {
+                Ok(PacketType::Return) => {
+                    return matches!(link.get_i64(), Ok(2)) && link.new_packet().is_ok()
+                },
+                Ok(_) => {
+                    if link.new_packet().is_err() {
+                        return false;
+                    }
+                },
+                Err(_) => return false,
+            }
+        }
+    }
+
+    /// Discard any packets that have already been received from this kernel, returning
+    /// `false` if an error occurs.
+    fn discard_pending_packets(&mut self) -> bool {
+        let link = self.link();
+
+        while link.is_ready() {
+            if link.next_packet().is_err() || link.new_packet().is_err() {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Terminate this kernel process, ignoring any errors.
+    fn kill(self) {
+        let WolframKernelProcess { mut process, link } = self;
+
+        drop(link);
+
+        let _ = process.kill();
+        let _ = process.wait();
+    }
+}
+
+impl<'p> Deref for PooledKernel<'p> {
+    type Target = WolframKernelProcess;
+
+    fn deref(&self) -> &WolframKernelProcess {
+        self.kernel.as_ref().expect("PooledKernel: kernel was already returned")
+    }
+}
+
+impl<'p> DerefMut for PooledKernel<'p> {
+    fn deref_mut(&mut self) -> &mut WolframKernelProcess {
+        self.kernel.as_mut().expect("PooledKernel: kernel was already returned")
+    }
+}
+
+impl<'p> Drop for PooledKernel<'p> {
+    fn drop(&mut self) {
+        // Discard any packets left unread by the holder of this guard, so that e.g. a
+        // stale `ReturnPacket` is not mistaken for the result of the next health check.
+        let kernel = self.kernel.take().and_then(|mut kernel| {
+            if kernel.discard_pending_packets() {
+                Some(kernel)
+            } else {
+                kernel.kill();
+                None
+            }
+        });
+
+        self.pool.return_slot(kernel);
+    }
+}
@@ -140,9 +140,16 @@
 #![warn(missing_docs)]
 
 
+mod bytes;
+mod convert;
 mod env;
 mod error;
+#[cfg(feature = "chrono")]
+mod datetime;
 mod link_server;
+mod link_state;
+mod mark;
+mod schema;
 mod wait;
 
 mod get;
@@ -179,13 +186,30 @@ use wstp_sys::{WSErrorMessage, WSReady, WSReleaseErrorMessage, WSLINK};
 pub use wstp_sys as sys;
 
 pub use crate::{
-    env::shutdown,
+    bytes::expr_from_bytes,
+    convert::{FromLink, ToLink},
+    env::{
+        disable_signal_handlers, library_version, raw_stdenv, shutdown, LibraryVersion,
+    },
     error::Error,
-    get::{Array, LinkStr, Token, TokenType},
+    get::{
+        Array, ArrayElementType, ArrayOrder, LinkStr, NumericArray, NumericArrayData,
+        Token, TokenType,
+    },
     link_server::LinkServer,
+    put::AssociationBuilder,
+    schema::Schema,
     strx::{Ucs2Str, Utf16Str, Utf32Str, Utf8Str},
+    wait::{ready_parallel, Timeout},
 };
 
+/// Derive macros for [`ToLink`] and [`FromLink`].
+#[cfg(feature = "derive")]
+pub use wstp_derive::{FromLink, ToLink};
+
+#[doc(hidden)]
+pub use crate::convert::__private;
+
 // TODO: Make this function public from `wstp`?
 pub(crate) use env::with_raw_stdenv;
 
@@ -235,6 +259,66 @@ impl Link {
     ///   single field of type [`WSLINK`][crate::sys::WSLINK].
     #[ref_cast::ref_cast_custom]
     pub unsafe fn unchecked_ref_cast_mut(from: &mut WSLINK) -> &mut Self;
+
+    /// Call `func` with a [`Link`] that borrows `raw_link` for the duration of the call.
+    ///
+    /// The [`Link`] passed to `func` does not own `raw_link`:
+    /// [`WSClose()`][sys::WSClose] is not called on `raw_link` when `func` returns.
+    /// This is a scoped alternative to [`Link::unchecked_ref_cast_mut()`], intended for
+    /// callbacks that are given a `WSLINK` owned by someone else, like LibraryLink
+    /// functions loaded via [`LibraryFunctionLoad`][LibraryFunctionLoad].
+    ///
+    /// Settings made on the borrowed link, like [`Link::set_max_array_elements()`], are
+    /// reverted when `func` returns.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::{sys::WSLINK, Link};
+    ///
+    /// // A function that is given a WSLINK by its caller.
+    /// unsafe extern "C" fn callback(mut raw_link: WSLINK) -> i64 {
+    ///     Link::with_borrowed(&mut raw_link, |link| link.get_i64().unwrap())
+    /// }
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    /// link.put_i64(5).unwrap();
+    ///
+    /// assert_eq!(unsafe { callback(link.raw_link()) }, 5);
+    ///
+    /// // `link` is still usable.
+    /// link.put_i64(6).unwrap();
+    /// assert_eq!(link.get_i64(), Ok(6));
+    /// ```
+    ///
+    /// [LibraryFunctionLoad]: https://reference.wolfram.com/language/ref/LibraryFunctionLoad.html
+    ///
+    /// # Safety
+    ///
+    /// `raw_link` must be validly initialized, and must not be used by anything else
+    /// while `func` is running.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `raw_link` is null.
+    pub unsafe fn with_borrowed<R, F>(raw_link: &mut WSLINK, func: F) -> R
+    where
+        F: FnOnce(&mut Link) -> R,
+    {
+        assert!(!raw_link.is_null(), "Link::with_borrowed(): WSLINK is null");
+
+        let raw: WSLINK = *raw_link;
+
+        // Don't let settings made through the borrowed link, like
+        // `Link::set_max_array_elements()`, outlive the borrow.
+        let saved = link_state::save(raw);
+
+        let result = func(Link::unchecked_ref_cast_mut(raw_link));
+
+        link_state::restore(raw, saved);
+
+        result
+    }
 }
 
 /// # Safety
@@ -249,6 +333,14 @@ impl Link {
 unsafe impl Send for Link {}
 
 /// Transport protocol used to communicate between two [`Link`] end points.
+///
+/// # Unix domain sockets
+///
+/// WSTP does not support communicating over a Unix domain socket, and a [`Link`]
+/// cannot adopt an existing [`UnixStream`](https://doc.rust-lang.org/std/os/unix/net/struct.UnixStream.html) (see
+/// [`Link::tcpip_connect()`]). For local communication between processes, use
+/// [`SharedMemory`][Protocol::SharedMemory], which is addressed by link name rather
+/// than by port, or a [`TCPIP`][Protocol::TCPIP] link bound to a loopback interface.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Protocol {
     /// Protocol type optimized for communication between two [`Link`] end points
@@ -263,6 +355,74 @@ pub enum Protocol {
     TCPIP,
 }
 
+//======================================
+// Expression reading configuration
+//======================================
+
+/// Options controlling how [`Link::get_expr_with()`] reads an expression.
+///
+/// The [`Default`] configuration matches the behavior of [`Link::get_expr()`].
+///
+/// # Example
+///
+/// ```
+/// use wstp::{ContextPolicy, GetExprConfig, Link};
+/// use wolfram_expr::{symbol::Context, Expr, Symbol};
+///
+/// let mut link = Link::new_loopback().unwrap();
+///
+/// link.put_symbol("x").unwrap();
+///
+/// let config = GetExprConfig {
+///     unresolved_context: ContextPolicy::DefaultTo(Context::global()),
+///     ..GetExprConfig::default()
+/// };
+///
+/// assert_eq!(
+///     link.get_expr_with(&config),
+///     Ok(Expr::symbol(Symbol::new("Global`x")))
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetExprConfig {
+    /// How to handle symbols that are received without a context.
+    pub unresolved_context: ContextPolicy,
+    /// Maximum depth of the expression to read. See [`Link::get_expr_limited()`].
+    pub max_depth: usize,
+}
+
+/// How [`Link::get_expr_with()`] handles symbols received without a context, like
+/// `x` instead of ``Global`x``.
+///
+/// There is no policy for keeping the symbol name as-is: every [`Symbol`] has a
+/// context, so a symbol with no context cannot be represented in an [`Expr`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ContextPolicy {
+    /// Return an error.
+    Error,
+    /// Treat the symbol as belonging to the given context.
+    DefaultTo(wolfram_expr::symbol::Context),
+}
+
+impl Default for GetExprConfig {
+    fn default() -> Self {
+        GetExprConfig {
+            unresolved_context: ContextPolicy::Error,
+            max_depth: Link::DEFAULT_MAX_EXPR_DEPTH,
+        }
+    }
+}
+
+/// Cumulative packet counts of a [`Link`], returned by [`Link::stats()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinkStats {
+    /// Number of packets ended using [`Link::end_packet()`].
+    pub packets_sent: u64,
+    /// Number of packets received using [`Link::raw_next_packet()`].
+    pub packets_received: u64,
+}
+
 //======================================
 // Urgent message types
 //======================================
@@ -390,7 +550,29 @@ impl Link {
     }
 
     /// Create a new named WSTP link using `protocol`.
+    ///
+    /// # Interaction
+    ///
+    /// The link is opened with the `MLDontInteract` link option, which prevents WSTP
+    /// from printing a "Link created on: .." message or otherwise prompting on the
+    /// console. [`Link::connect()`] uses the same option.
+    ///
+    /// # IntraProcess links
+    ///
+    /// [`IntraProcess`][Protocol::IntraProcess] links ignore the requested name, and
+    /// generate their own. To avoid silently creating a link with a different name than
+    /// was requested, an error is returned if `protocol` is `IntraProcess` and `name`
+    /// is not empty. Pass an empty name, and use [`Link::link_name()`] to get the
+    /// generated name, or use [`intra_process_channel()`].
     pub fn listen(protocol: Protocol, name: &str) -> Result<Self, Error> {
+        if protocol == Protocol::IntraProcess && !name.is_empty() {
+            return Err(Error::custom(format!(
+                "IntraProcess links do not support choosing a link name (requested \
+                 name: {:?}); pass an empty name and use Link::link_name()",
+                name
+            )));
+        }
+
         let protocol_string = protocol.to_string();
 
         let strings: &[&str] = &[
@@ -406,27 +588,119 @@ impl Link {
             "MLDontInteract",
         ];
 
-        Link::open_with_args(strings)
+        let link = Link::open_with_args(strings)?;
+
+        // Record the protocol of this listening link, for `Link::is_peer_present()`.
+        link_state::update(link.raw_link, |state| {
+            state.protocol = Some(protocol);
+            state.listening = true;
+        });
+
+        Ok(link)
     }
 
     /// Connect to an existing named WSTP link.
+    ///
+    /// # Interaction
+    ///
+    /// Like [`Link::listen()`], the link is opened with the `MLDontInteract` link
+    /// option, which prevents WSTP from prompting on the console (for example, to ask
+    /// for a link name when `name` is empty).
     pub fn connect(protocol: Protocol, name: &str) -> Result<Self, Error> {
-        Link::connect_with_options(protocol, name, &[])
+        // Prevent WSTP from prompting for input or printing messages, consistent with
+        // `Link::listen()`.
+        Link::connect_with_options(protocol, name, &["MLDontInteract"])
+    }
+
+    /// Connect to an existing named WSTP link, and activate the new link.
+    ///
+    /// This is equivalent to calling [`Link::connect()`] followed by
+    /// [`Link::activate()`]. Activation will block until the listening link has also
+    /// been activated. Use [`Link::connect()`] if the link needs to be configured
+    /// before it is activated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::thread;
+    /// use wstp::{Link, Protocol};
+    ///
+    /// let mut listener = Link::listen(Protocol::SharedMemory, "").unwrap();
+    /// let name = listener.link_name();
+    ///
+    /// let listening_thread = thread::spawn(move || {
+    ///     listener.activate().unwrap();
+    ///     listener.put_str("hello!").unwrap();
+    ///     listener.flush().unwrap();
+    /// });
+    ///
+    /// let mut link = Link::connect_activated(Protocol::SharedMemory, &name).unwrap();
+    /// assert_eq!(link.get_string().unwrap(), "hello!");
+    ///
+    /// listening_thread.join().unwrap();
+    /// ```
+    pub fn connect_activated(protocol: Protocol, name: &str) -> Result<Self, Error> {
+        let mut link = Link::connect(protocol, name)?;
+
+        let () = link.activate()?;
+
+        Ok(link)
+    }
+
+    /// Create a matched pair of connected [`IntraProcess`][Protocol::IntraProcess]
+    /// links.
+    ///
+    /// `IntraProcess` links cannot be given a name by [`Link::listen()`], and generate
+    /// their own, so a connecting link must be created using the name reported by
+    /// [`Link::link_name()`] on the listening link. This function performs that
+    /// handshake internally, and returns the `(listener, connector)` pair with both
+    /// links already activated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let (mut a, mut b) = Link::intra_process_pair().unwrap();
+    ///
+    /// a.put_i64(42).unwrap();
+    /// a.flush().unwrap();
+    ///
+    /// assert_eq!(b.get_i64().unwrap(), 42);
+    /// ```
+    ///
+    /// # Testing
+    ///
+    /// Because both links are returned already activated, this is a convenient way to
+    /// test code that communicates over a full-duplex link, without needing to spawn
+    /// threads to activate each end, or to wait for the other end to connect.
+    ///
+    /// See also: [`channel()`]
+    #[doc(alias = "pipe")]
+    pub fn intra_process_pair() -> Result<(Link, Link), Error> {
+        channel(Protocol::IntraProcess)
     }
 
     /// Create a new WSTP [`TCPIP`][Protocol::TCPIP] link bound to `addr`.
     ///
     /// If `addr` yields multiple addresses, listening will be attempted with each of the
     /// addresses until one succeeds and returns the listener. If none of the addresses
-    /// succeed in creating a listener, the error returned from the last attempt
-    /// (the last address) is returned.
+    /// succeed in creating a listener, an error listing each address and the reason
+    /// it failed is returned.
+    ///
+    /// # Interface
+    ///
+    /// The listener is bound to the network interface identified by the IP address of
+    /// `addr`, not just to its port. For example, a listener created using
+    /// `Link::tcpip_listen("127.0.0.1:8080")` will only accept connections made to the
+    /// `127.0.0.1` loopback interface. Use an unspecified address like `0.0.0.0` to accept
+    /// connections on every IPv4 interface.
+    ///
+    /// This matches the behavior of [`LinkServer::bind()`], which also binds to the
+    /// interface of the address it is given.
     pub fn tcpip_listen<A: net::ToSocketAddrs>(addr: A) -> Result<Self, Error> {
-        let addrs = addr.to_socket_addrs().map_err(|err| {
-            Error::custom(format!("error connecting to TCPIP Link address: {}", err))
-        })?;
-
         // Try each address, returning the first one which binds for listening successfully.
-        for_each_addr(addrs.collect(), |addr| {
+        for_each_addr(addr, "error connecting to TCPIP Link address", |addr| {
             Link::listen(Protocol::TCPIP, &tcpip_link_name(&addr))
         })
     }
@@ -435,15 +709,21 @@ impl Link {
     ///
     /// If `addr` yields multiple addresses, a connection will be attempted with each of
     /// the addresses until a connection is successful. If none of the addresses result
-    /// in a successful connection, the error returned from the last connection attempt
-    /// (the last address) is returned.
+    /// in a successful connection, an error listing each address and the reason the
+    /// connection to it failed is returned.
+    ///
+    /// # Existing connections
+    ///
+    /// WSTP always establishes [`TCPIP`][Protocol::TCPIP] connections itself, and does
+    /// not provide a way to adopt an already-connected socket. Consequently, it is not
+    /// possible to construct a [`Link`] from an existing [`std::net::TcpStream`].
+    ///
+    /// To run WSTP over a connection negotiated by other means (for example, a TLS
+    /// session), forward a local port over that connection, and connect the [`Link`] to
+    /// the local port.
     pub fn tcpip_connect<A: net::ToSocketAddrs>(addr: A) -> Result<Self, Error> {
-        let addrs = addr.to_socket_addrs().map_err(|err| {
-            Error::custom(format!("error connecting to TCPIP Link address: {}", err))
-        })?;
-
         // Try each address, returning the first one which connects successfully.
-        for_each_addr(addrs.collect(), |addr| {
+        for_each_addr(addr, "error connecting to TCPIP Link address", |addr| {
             Link::connect(Protocol::TCPIP, &tcpip_link_name(&addr))
         })
     }
@@ -452,17 +732,15 @@ impl Link {
     ///
     /// If `addrs` yields multiple addresses, a connection will be attempted with each of
     /// the addresses until a connection is successful. If none of the addresses result
-    /// in a successful connection, the error returned from the last connection attempt
-    /// (the last address) is returned.
+    /// in a successful connection, an error listing each address and the reason the
+    /// connection to it failed is returned.
+    ///
+    /// The returned link has already been activated.
     pub fn connect_to_link_server<A: net::ToSocketAddrs>(
         addrs: A,
     ) -> Result<Self, Error> {
-        let addrs = addrs.to_socket_addrs().map_err(|err| {
-            Error::custom(format!("error connecting to LinkServer address: {}", err))
-        })?;
-
         // Try each address, returning the first one which connects successfully.
-        for_each_addr(addrs.collect(), |addr| {
+        for_each_addr(addrs, "error connecting to LinkServer address", |addr| {
             let mut link = Link::connect_with_options(
                 Protocol::TCPIP,
                 &tcpip_link_name(&addr),
@@ -471,7 +749,8 @@ impl Link {
                 &["MLUseUUIDTCPIPConnection"],
             )?;
 
-            // TODO: Should we activate here, or let the caller do this?
+            // Note: Like `Link::connect_activated()`, the returned link is already
+            //       activated.
             let () = link.activate()?;
 
             return Ok(link);
@@ -502,7 +781,11 @@ impl Link {
             strings.extend(options);
         }
 
-        Link::open_with_args(&strings)
+        let link = Link::open_with_args(&strings)?;
+
+        link_state::update(link.raw_link, |state| state.protocol = Some(protocol));
+
+        Ok(link)
     }
 
     /// *WSTP C API Documentation:* [`WSOpenArgcArgv()`](https://reference.wolfram.com/language/ref/c/WSOpenArgcArgv.html)
@@ -553,14 +836,49 @@ impl Link {
             return Err(Error::from_code(err));
         }
 
-        Ok(Link { raw_link })
+        let link = unsafe { Link::unchecked_new(raw_link) };
+
+        if !link.is_loopback() {
+            link_state::update(raw_link, |state| state.unactivated = true);
+        }
+
+        Ok(link)
     }
 
     /// Construct a [`Link`] from a raw [`WSLINK`] pointer.
+    ///
+    /// The returned [`Link`] owns `raw_link`. Any settings made on a previous link with
+    /// the same address, like [`Link::set_max_array_elements()`], are discarded.
     pub unsafe fn unchecked_new(raw_link: WSLINK) -> Self {
+        link_state::remove(raw_link);
+
         Link { raw_link }
     }
 
+    /// Activate this link, blocking until the connection to the other endpoint has been
+    /// established.
+    ///
+    /// A link created using [`Link::listen()`] or [`Link::connect()`] must be activated
+    /// before data is read from or written to it. See also [`Link::activate_timeout()`].
+    ///
+    /// Use [`Link::is_activated()`] to check whether a link still needs to be
+    /// activated.
+    ///
+    /// # Checking for a peer
+    ///
+    /// Use [`Link::is_peer_present()`] to check whether a listening link has a pending
+    /// connection before calling `activate()`. That check is not available for every
+    /// [`Protocol`], so to avoid blocking indefinitely when the peer may never appear,
+    /// use [`Link::activate_timeout()`] instead.
+    ///
+    /// # Errors
+    ///
+    /// [`WSActivate()`][sys::WSActivate] either succeeds or fails as a whole; there is
+    /// no partial success. If activation fails, the error code set on the link is
+    /// returned. Use [`Error::is_retryable()`] to distinguish failures that may succeed
+    /// on a new attempt (e.g. the peer closed the connection during the handshake) from
+    /// those that will not (e.g. a protocol mismatch).
+    ///
     /// *WSTP C API Documentation:* [`WSActivate()`](https://reference.wolfram.com/language/ref/c/WSActivate.html)
     pub fn activate(&mut self) -> Result<(), Error> {
         // Note: WSActivate() returns 0 in the event of an error, and sets an error
@@ -569,21 +887,145 @@ impl Link {
             return Err(self.error_or_unknown());
         }
 
+        link_state::update(self.raw_link, |state| state.unactivated = false);
+
         Ok(())
     }
 
+    /// Returns `false` if this link was created by [`Link::listen()`],
+    /// [`Link::connect()`], or [`Link::open_with_args()`], and has not yet been
+    /// activated using [`Link::activate()`] or [`Link::activate_timeout()`].
+    ///
+    /// Reading from or writing to a link that has not been activated fails with errors
+    /// that do not mention activation. This method can be used to detect that mistake
+    /// and report it clearly.
+    ///
+    /// The WSTP C API does not expose whether a link has been activated, so this state
+    /// is recorded by this crate. Links that this crate did not open, e.g. those
+    /// constructed using [`Link::unchecked_new()`], as well as loopback links and links
+    /// accepted by a [`LinkServer`], are assumed to be activated. A link activated by
+    /// calling [`WSActivate()`][sys::WSActivate] directly is not detected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::{Link, Protocol};
+    ///
+    /// let mut listener = Link::listen(Protocol::IntraProcess, "").unwrap();
+    /// let mut connector =
+    ///     Link::connect(Protocol::IntraProcess, &listener.link_name()).unwrap();
+    ///
+    /// assert!(!listener.is_activated());
+    ///
+    /// let thread = std::thread::spawn(move || {
+    ///     listener.activate().unwrap();
+    ///     listener.is_activated()
+    /// });
+    ///
+    /// connector.activate().unwrap();
+    ///
+    /// assert!(connector.is_activated());
+    /// assert!(thread.join().unwrap());
+    ///
+    /// assert!(Link::new_loopback().unwrap().is_activated());
+    /// ```
+    pub fn is_activated(&self) -> bool {
+        link_state::with(self.raw_link, |state| !state.unactivated)
+    }
+
+    /// Check whether the peer of this link is present, returning `true` if that is
+    /// not known.
+    ///
+    /// This can be used to avoid calling [`Link::activate()`] on a listening link when
+    /// it would block because nothing has connected to it yet. The result depends on
+    /// how the link was created:
+    ///
+    /// * **[`IntraProcess`][Protocol::IntraProcess] and
+    ///   [`SharedMemory`][Protocol::SharedMemory] links created by [`Link::listen()`]
+    ///   that have not been activated:** returns whether a connection to this link is
+    ///   pending, using [`WSReady()`][sys::WSReady].
+    /// * **[`TCPIP`][Protocol::TCPIP] links created by [`Link::listen()`] that have not
+    ///   been activated:** returns `true`. WSTP does not report pending TCP/IP
+    ///   connections before the link is activated.
+    /// * **Links created by [`Link::connect()`] that have not been activated:** returns
+    ///   `true`. A connecting link only finds out whether its peer is present when it
+    ///   is activated.
+    /// * **All other links,** including activated links: returns `true`. A link that
+    ///   has been activated had a peer when it was activated; whether the peer has since
+    ///   closed its end is reported by the errors returned when using the link.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::{Link, Protocol};
+    ///
+    /// let listener = Link::listen(Protocol::SharedMemory, "").unwrap();
+    ///
+    /// // Nothing has connected to `listener`.
+    /// assert!(!listener.is_peer_present());
+    /// ```
+    pub fn is_peer_present(&self) -> bool {
+        let listening_protocol: Option<Protocol> =
+            link_state::with(self.raw_link, |state| {
+                match state.unactivated && state.listening {
+                    true => state.protocol.clone(),
+                    false => None,
+                }
+            });
+
+        match listening_protocol {
+            Some(Protocol::IntraProcess | Protocol::SharedMemory) => self.is_ready(),
+            Some(Protocol::TCPIP) | None => true,
+        }
+    }
+
     /// Close this end of the link.
     ///
+    /// This is equivalent to dropping the link. Any data written to this link that has
+    /// not yet been sent is discarded without reporting an error. Use
+    /// [`Link::close_checked()`] to detect that case.
+    ///
     /// *WSTP C API Documentation:* [`WSClose()`](https://reference.wolfram.com/language/ref/c/WSClose.html)
     pub fn close(self) {
         // Note: The link is closed when `self` is dropped.
     }
+
+    /// Flush any buffered data, and then close this end of the link.
+    ///
+    /// An error is returned if the buffered data could not be sent. The link is closed
+    /// whether or not an error is returned.
+    ///
+    /// [`WSClose()`][sys::WSClose] itself does not report errors, so flushing is the
+    /// only part of closing a link that can fail in a detectable way. Dropping a
+    /// [`Link`], or calling [`Link::close()`], does not flush and ignores any error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Protocol;
+    ///
+    /// let (mut a, mut b) = wstp::channel(Protocol::SharedMemory).unwrap();
+    ///
+    /// a.put_str("goodbye").unwrap();
+    /// a.close_checked().unwrap();
+    ///
+    /// assert_eq!(b.get_string().unwrap(), "goodbye");
+    /// ```
+    pub fn close_checked(mut self) -> Result<(), Error> {
+        let result = self.flush();
+
+        // Note: The link is closed when `self` is dropped.
+        drop(self);
+
+        result
+    }
 }
 
 /// Create a full-duplex WSTP communication channel with two [`Link`] endpoints.
 ///
 /// This function is a convenient alternative to manually using
-/// [`Link::listen()`] and [`Link::connect()`] to create a channel.
+/// [`Link::listen()`] and [`Link::connect()`] to create a channel. Both returned links
+/// have already been activated.
 ///
 /// # Example
 ///
@@ -603,11 +1045,12 @@ impl Link {
 /// assert_eq!(a.get_string().unwrap(), "from b to a");
 /// assert_eq!(b.get_string().unwrap(), "from a to b");
 /// ```
+#[doc(alias = "pipe")]
 pub fn channel(protocol: Protocol) -> Result<(Link, Link), Error> {
     let mut listener = Link::listen(protocol.clone(), "")?;
     let mut connecter = Link::connect(protocol, &listener.link_name())?;
 
-    let listener = std::thread::spawn(move || {
+    let listener = std::thread::spawn(move || -> Result<Link, Error> {
         let () = listener.activate()?;
         Ok(listener)
     });
@@ -619,6 +1062,129 @@ pub fn channel(protocol: Protocol) -> Result<(Link, Link), Error> {
     Ok((listener, connecter))
 }
 
+/// Listening end of an [`IntraProcess`][Protocol::IntraProcess] link, created by
+/// [`intra_process_channel()`].
+///
+/// Use [`LinkListener::accept()`] to wait for a peer to connect.
+#[derive(Debug)]
+pub struct LinkListener {
+    link: Link,
+}
+
+/// Create a listening [`IntraProcess`][Protocol::IntraProcess] link, returning the
+/// listener and the name a peer should use to connect to it.
+///
+/// `IntraProcess` links cannot be given a name by [`Link::listen()`], and generate their
+/// own, so the name must be read from the listener after it is created. This function
+/// performs that step, and returns the generated name to be handed to a peer, which
+/// can connect using [`Link::connect()`].
+///
+/// See also [`Link::intra_process_pair()`], which creates both ends of the link at
+/// once.
+///
+/// # Example
+///
+/// ```
+/// use std::thread;
+/// use wstp::{Link, Protocol};
+///
+/// let (listener, name) = wstp::intra_process_channel().unwrap();
+///
+/// let peer = thread::spawn(move || {
+///     let mut link = Link::connect_activated(Protocol::IntraProcess, &name).unwrap();
+///     link.put_str("hello").unwrap();
+///     link.flush().unwrap();
+///     link
+/// });
+///
+/// let mut link = listener.accept().unwrap();
+///
+/// assert_eq!(link.get_string().unwrap(), "hello");
+///
+/// let _peer = peer.join().unwrap();
+/// ```
+pub fn intra_process_channel() -> Result<(LinkListener, String), Error> {
+    let link = Link::listen(Protocol::IntraProcess, "")?;
+    let name = link.link_name();
+
+    Ok((LinkListener { link }, name))
+}
+
+impl LinkListener {
+    /// Get the generated name of this listener.
+    pub fn name(&self) -> String {
+        self.link.link_name()
+    }
+
+    /// Wait for a peer to connect, and return the activated link.
+    ///
+    /// This blocks until a peer has connected to this listener.
+    pub fn accept(self) -> Result<Link, Error> {
+        let LinkListener { mut link } = self;
+
+        let () = link.activate()?;
+
+        Ok(link)
+    }
+}
+
+/// Get the link protocols supported by the WSTP library this program is linked
+/// against.
+///
+/// Each protocol is probed by creating a listening link using that protocol with an
+/// automatically chosen name, and then immediately closing it. A protocol is
+/// considered available if the link could be created. This can be used by portable
+/// programs to choose a protocol that will work on the current platform, instead of
+/// failing later when calling [`Link::listen()`] or [`Link::connect()`].
+///
+/// Probing a [`TCPIP`][Protocol::TCPIP] link briefly binds a network port chosen by
+/// WSTP.
+///
+/// # Example
+///
+/// ```
+/// use wstp::Protocol;
+///
+/// let protocols = wstp::available_protocols();
+///
+/// let protocol = if protocols.contains(&Protocol::SharedMemory) {
+///     Protocol::SharedMemory
+/// } else {
+///     Protocol::TCPIP
+/// };
+/// ```
+pub fn available_protocols() -> Vec<Protocol> {
+    [Protocol::IntraProcess, Protocol::SharedMemory, Protocol::TCPIP]
+        .into_iter()
+        .filter(|protocol| Link::listen(protocol.clone(), "").is_ok())
+        .collect()
+}
+
+/// Write `expr` to a new loopback link, and then read it back.
+///
+/// This is primarily intended as a testing and validation aid: it can be used to check
+/// that an [`Expr`] can be written to a link, and that it is read back unchanged.
+///
+/// An error is returned if `expr` could not be written or read, e.g. because it is
+/// nested more deeply than [`Link::DEFAULT_MAX_EXPR_DEPTH`].
+///
+/// # Example
+///
+/// ```
+/// use wolfram_expr::{Expr, Symbol};
+///
+/// let expr = Expr::normal(Symbol::new("System`List"), vec![Expr::from(1)]);
+///
+/// assert_eq!(wstp::roundtrip_expr(&expr), Ok(expr));
+/// ```
+pub fn roundtrip_expr(expr: &Expr) -> Result<Expr, Error> {
+    let mut link = Link::new_loopback()?;
+
+    link.put_expr(expr)?;
+
+    link.get_expr()
+}
+
 /// # Link properties
 impl Link {
     /// Get the name of this link.
@@ -633,6 +1199,39 @@ impl Link {
         }
     }
 
+    /// Set a description of this link, used to label it in diagnostics.
+    ///
+    /// WSTP does not provide a way to attach a description to a link, so the
+    /// description is stored by this crate, and is only visible in the [`Display`]
+    /// output of this [`Link`]. It does not change the
+    /// [`Link::link_name()`], and is not visible to the peer of this link.
+    ///
+    /// Descriptions set on borrowed links are removed in the same way as the limit set
+    /// by [`Link::set_max_array_elements()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.set_description("request-buffer");
+    ///
+    /// assert_eq!(link.description().as_deref(), Some("request-buffer"));
+    /// assert!(link.to_string().contains("description=\"request-buffer\""));
+    /// ```
+    pub fn set_description(&mut self, desc: &str) {
+        link_state::update(self.raw_link, |state| {
+            state.description = Some(desc.to_owned());
+        })
+    }
+
+    /// Get the description set using [`Link::set_description()`], if any.
+    pub fn description(&self) -> Option<String> {
+        link_state::with(self.raw_link, |state| state.description.clone())
+    }
+
     /// Check if there is data ready to be read from this link.
     ///
     /// *WSTP C API Documentation:* [`WSReady()`](https://reference.wolfram.com/language/ref/c/WSReady.html)
@@ -676,30 +1275,70 @@ impl Link {
             string
         };
 
+        // WSEABORT is also returned when reading past the end of the data on a loopback
+        // link. Loopback links cannot be aborted, so that is what it means here.
+        let end_of_data = code == sys::WSEABORT && self.is_loopback() && !self.is_ready();
+
         return Some(Error {
             code: Some(code),
             message: string,
+            end_of_data,
+            timed_out: false,
         });
     }
 
-    /// Returns a string describing the last error to occur on this link.
+    /// Returns the code of the last error to occur on this link, or `None` if no error
+    /// has occurred.
     ///
-    /// TODO: If the most recent operation was successful, does the error message get
-    ///       cleared?
+    /// Unlike [`Link::error()`], this does not allocate the error message, which makes
+    /// it suitable for checking for errors on frequently executed paths.
     ///
-    /// *WSTP C API Documentation:* [`WSErrorMessage()`](https://reference.wolfram.com/language/ref/c/WSErrorMessage.html)
-    pub fn error_message(&self) -> Option<String> {
-        self.error().map(|Error { message, code: _ }| message)
-    }
-
-    /// Helper to create an [`Error`] instance even if the underlying link does not have
-    /// an error code set.
-    pub(crate) fn error_or_unknown(&self) -> Error {
-        self.error()
-            .unwrap_or_else(|| Error::custom("unknown error occurred on WSLINK".into()))
-    }
-
-    /// Clear errors on this link.
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// assert_eq!(link.error_code(), None);
+    ///
+    /// // Reading from an empty loopback link is an error.
+    /// assert!(link.get_i64().is_err());
+    ///
+    /// assert_eq!(link.error_code(), link.error().and_then(|err| err.code()));
+    /// ```
+    ///
+    /// *WSTP C API Documentation:* [`WSError()`](https://reference.wolfram.com/language/ref/c/WSError.html)
+    pub fn error_code(&self) -> Option<i32> {
+        let Link { raw_link } = *self;
+
+        let code: i32 = unsafe { sys::WSError(raw_link) };
+
+        if code == sys::MLEOK {
+            return None;
+        }
+
+        Some(code)
+    }
+
+    /// Returns a string describing the last error to occur on this link.
+    ///
+    /// TODO: If the most recent operation was successful, does the error message get
+    ///       cleared?
+    ///
+    /// *WSTP C API Documentation:* [`WSErrorMessage()`](https://reference.wolfram.com/language/ref/c/WSErrorMessage.html)
+    pub fn error_message(&self) -> Option<String> {
+        self.error().map(|Error { message, .. }| message)
+    }
+
+    /// Helper to create an [`Error`] instance even if the underlying link does not have
+    /// an error code set.
+    pub(crate) fn error_or_unknown(&self) -> Error {
+        self.error()
+            .unwrap_or_else(|| Error::custom("unknown error occurred on WSLINK".into()))
+    }
+
+    /// Clear errors on this link.
     ///
     /// *WSTP C API Documentation:* [`WSClearError()`](https://reference.wolfram.com/language/ref/c/WSClearError.html)
     pub fn clear_error(&mut self) {
@@ -810,6 +1449,51 @@ impl Link {
 impl Link {
     /// Flush out any buffers containing data waiting to be sent on this link.
     ///
+    /// Flushing does not mark the end of a packet. To finish writing a packet and send
+    /// it immediately, use [`Link::send_packet()`], which calls [`Link::end_packet()`]
+    /// followed by `flush()`.
+    ///
+    /// # Streaming
+    ///
+    /// Flushing sends every token written so far, even if the expression being written
+    /// is incomplete. This makes it possible to stream the elements of a large
+    /// expression: write the function header, and then write and flush elements as they
+    /// are computed. The peer can read the header and each flushed element (e.g. after
+    /// [`Link::is_ready()`] returns `true`) before the writer has finished.
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let (mut writer, mut reader) = Link::intra_process_pair().unwrap();
+    ///
+    /// writer.put_function("System`List", 2).unwrap();
+    /// writer.put_i64(1).unwrap();
+    /// writer.flush().unwrap();
+    ///
+    /// // The reader can consume the incomplete expression.
+    /// assert_eq!(reader.test_head("System`List"), Ok(2));
+    /// assert_eq!(reader.get_i64(), Ok(1));
+    ///
+    /// writer.put_i64(2).unwrap();
+    /// writer.flush().unwrap();
+    ///
+    /// assert_eq!(reader.get_i64(), Ok(2));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs (e.g. because the peer link has been closed), some of the
+    /// buffered data may already have been sent while the rest was not. WSTP does not
+    /// report which expressions were delivered, so the data stream seen by the peer may
+    /// end partway through an expression. [`Link::bytes_to_put()`] can be used to check
+    /// how much data is still buffered, and [`Link::try_flush()`] to check whether a
+    /// flush sent everything.
+    ///
+    /// A link whose flush failed because the connection was lost (e.g. with the error
+    /// code [`WSECLOSED`][sys::WSECLOSED]) cannot be recovered, and should be closed. For
+    /// other errors, [`Link::clear_error()`] may be used to attempt to continue using
+    /// the link.
+    ///
     /// *WSTP C API Documentation:* [`WSFlush()`](https://reference.wolfram.com/language/ref/c/WSFlush.html)
     pub fn flush(&mut self) -> Result<(), Error> {
         if unsafe { sys::WSFlush(self.raw_link) } == 0 {
@@ -819,6 +1503,133 @@ impl Link {
         Ok(())
     }
 
+    /// Flush any buffered data, returning `true` if all data written to this link has
+    /// been handed off to the underlying transport.
+    ///
+    /// If `false` is returned, some data is still buffered (see
+    /// [`Link::bytes_to_put()`]), and the caller may call `try_flush()` again later to
+    /// retry, or give up and close the link. Unlike [`Link::flush_and_sync()`], this
+    /// function does not block waiting for the buffered data to be accepted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Protocol;
+    ///
+    /// let (mut a, mut b) = wstp::channel(Protocol::IntraProcess).unwrap();
+    ///
+    /// a.put_i64(5).unwrap();
+    ///
+    /// while !a.try_flush().unwrap() {
+    ///     // Do other work, or give up after some number of attempts.
+    /// }
+    ///
+    /// assert_eq!(b.get_i64(), Ok(5));
+    /// ```
+    pub fn try_flush(&mut self) -> Result<bool, Error> {
+        let () = self.flush()?;
+
+        Ok(self.bytes_to_put()? == 0)
+    }
+
+    /// Flush any buffered data, and then block until the underlying transport has
+    /// accepted all data written to this link.
+    ///
+    /// This is useful to ensure that data written to this link is not lost if the link
+    /// is closed immediately afterwards.
+    ///
+    /// Note that "accepted by the transport" has a protocol-specific meaning:
+    ///
+    /// * [`SharedMemory`][Protocol::SharedMemory] and
+    ///   [`IntraProcess`][Protocol::IntraProcess] links: the data has been copied into
+    ///   memory shared with the peer link, and will be available to the peer even if
+    ///   this link is closed.
+    /// * [`TCPIP`][Protocol::TCPIP] links: the data has been written to the operating
+    ///   system socket. This does not guarantee that the peer has received or read the
+    ///   data. If an application-level acknowledgement is needed, the peer must send
+    ///   one explicitly.
+    ///
+    /// See also [`Link::bytes_to_put()`].
+    pub fn flush_and_sync(&mut self) -> Result<(), Error> {
+        let () = self.flush()?;
+
+        while self.bytes_to_put()? != 0 {
+            std::thread::sleep(Timeout::DEFAULT_POLL_INTERVAL);
+
+            let () = self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of bytes written to this link that are still buffered and
+    /// have not yet been sent.
+    ///
+    /// *WSTP C API Documentation:* [`WSBytesToPut()`](https://reference.wolfram.com/language/ref/c/WSBytesToPut.html)
+    pub fn bytes_to_put(&mut self) -> Result<usize, Error> {
+        let mut count: std::os::raw::c_int = 0;
+
+        if unsafe { sys::WSBytesToPut(self.raw_link, &mut count) } == 0 {
+            return Err(self.error_or_unknown());
+        }
+
+        let count = usize::try_from(count).expect("WSBytesToPut count overflows usize");
+
+        Ok(count)
+    }
+
+    /// Returns the number of bytes that have been received by this link and are
+    /// available to be read without blocking.
+    ///
+    /// *WSTP C API Documentation:* [`WSBytesToGet()`](https://reference.wolfram.com/language/ref/c/WSBytesToGet.html)
+    pub fn bytes_to_get(&mut self) -> Result<usize, Error> {
+        let mut count: std::os::raw::c_int = 0;
+
+        if unsafe { sys::WSBytesToGet(self.raw_link, &mut count) } == 0 {
+            return Err(self.error_or_unknown());
+        }
+
+        let count = usize::try_from(count).expect("WSBytesToGet count overflows usize");
+
+        Ok(count)
+    }
+
+    /// Get the number of packets sent and received by this link.
+    ///
+    /// The counts are maintained on a best-effort basis by this crate, because WSTP
+    /// does not track them: packets sent or received using raw `sys` functions are not
+    /// counted.
+    ///
+    /// Cumulative byte counts are not available. WSTP encodes and buffers the data
+    /// written to a link internally, and only reports the number of bytes that are
+    /// *currently* buffered. Use [`Link::bytes_to_put()`] and [`Link::bytes_to_get()`]
+    /// to sample those.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::{Link, LinkStats};
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_function("System`ReturnPacket", 1).unwrap();
+    /// link.put_i64(1).unwrap();
+    /// link.end_packet().unwrap();
+    ///
+    /// assert_eq!(link.raw_next_packet(), Ok(wstp::sys::RETURNPKT));
+    ///
+    /// assert_eq!(
+    ///     link.stats(),
+    ///     LinkStats {
+    ///         packets_sent: 1,
+    ///         packets_received: 1,
+    ///     }
+    /// );
+    /// ```
+    pub fn stats(&self) -> LinkStats {
+        link_state::with(self.raw_link, |state| state.stats)
+    }
+
     /// *WSTP C API Documentation:* [`WSGetNext()`](https://reference.wolfram.com/language/ref/c/WSGetNext.html)
     pub fn raw_get_next(&mut self) -> Result<i32, Error> {
         let type_ = unsafe { sys::WSGetNext(self.raw_link) };
@@ -838,6 +1649,10 @@ impl Link {
             return Err(self.error_or_unknown());
         }
 
+        link_state::reset_reads(self.raw_link);
+
+        link_state::update(self.raw_link, |state| state.stats.packets_received += 1);
+
         Ok(type_)
     }
 
@@ -847,12 +1662,130 @@ impl Link {
             return Err(self.error_or_unknown());
         }
 
+        link_state::reset_reads(self.raw_link);
+
         Ok(())
     }
 
+    /// Default maximum depth of expressions read by [`Link::get_expr()`].
+    ///
+    /// This matches the default value of
+    /// [`$RecursionLimit`](https://reference.wolfram.com/language/ref/$RecursionLimit.html)
+    /// in the Wolfram Language.
+    pub const DEFAULT_MAX_EXPR_DEPTH: usize = 1024;
+
     /// Read an expression off of this link.
+    ///
+    /// An error is returned if the incoming expression is nested more than
+    /// [`Link::DEFAULT_MAX_EXPR_DEPTH`] levels deep. Use [`Link::get_expr_limited()`] to
+    /// read an expression using a different maximum depth.
     pub fn get_expr(&mut self) -> Result<Expr, Error> {
-        self.get_expr_with_resolver(&mut |_| None)
+        self.get_expr_limited(Link::DEFAULT_MAX_EXPR_DEPTH)
+    }
+
+    /// Read an expression off of this link, returning an error if the incoming
+    /// expression is nested more than `max_depth` levels deep.
+    ///
+    /// This function does not recurse on the structure of the incoming expression, so
+    /// reading expressions sent by an untrusted peer will not overflow the stack.
+    ///
+    /// See [`Link::put_expr_limited()`] for the definition of expression depth.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// // {{1}}
+    /// link.put_function("System`List", 1).unwrap();
+    /// link.put_function("System`List", 1).unwrap();
+    /// link.put_i64(1).unwrap();
+    ///
+    /// assert!(link.get_expr_limited(2).is_err());
+    /// ```
+    pub fn get_expr_limited(&mut self, max_depth: usize) -> Result<Expr, Error> {
+        self.get_expr_with_resolver_limited(&mut |_| None, max_depth)
+    }
+
+    /// Read every complete expression currently available on this link, in order.
+    ///
+    /// Expressions are read until [`Link::is_ready()`] reports that no more data is
+    /// available. This is intended for loopback links used as buffers, where it reads
+    /// back every expression that was written to the link. An empty `Vec` is returned
+    /// if the link is empty.
+    ///
+    /// If reading any expression fails, an error describing the index of the
+    /// expression that could not be read is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    /// use wolfram_expr::Expr;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// assert_eq!(link.get_all_exprs(), Ok(vec![]));
+    ///
+    /// link.put_i64(1).unwrap();
+    /// link.put_str("two").unwrap();
+    ///
+    /// assert_eq!(
+    ///     link.get_all_exprs(),
+    ///     Ok(vec![Expr::from(1), Expr::string("two")])
+    /// );
+    /// ```
+    pub fn get_all_exprs(&mut self) -> Result<Vec<Expr>, Error> {
+        let mut exprs = Vec::new();
+
+        while self.is_ready() {
+            let expr = self.get_expr().map_err(|err| {
+                err.with_context(format!(
+                    "error reading expression at index {}",
+                    exprs.len()
+                ))
+            })?;
+
+            exprs.push(expr);
+        }
+
+        Ok(exprs)
+    }
+
+    /// Read an expression off of this link, using the options specified by `config`.
+    ///
+    /// See [`GetExprConfig`] for an example.
+    pub fn get_expr_with(&mut self, config: &GetExprConfig) -> Result<Expr, Error> {
+        let GetExprConfig {
+            unresolved_context,
+            max_depth,
+        } = config;
+
+        match unresolved_context {
+            ContextPolicy::Error => {
+                self.get_expr_with_resolver_limited(&mut |_| None, *max_depth)
+            },
+            ContextPolicy::DefaultTo(context) => self.get_expr_with_resolver_limited(
+                &mut |name| Symbol::try_new(&format!("{}{}", context.as_str(), name)),
+                *max_depth,
+            ),
+        }
+    }
+
+    /// Read an expression, resolving symbols received without a context in the
+    /// ``System` `` context.
+    ///
+    /// Arrays written by e.g. [`Link::put_i64_array()`] have `List` heads without a
+    /// context, as do expressions sent by a Wolfram Kernel.
+    pub(crate) fn get_system_expr(&mut self) -> Result<Expr, Error> {
+        self.get_expr_with(&GetExprConfig {
+            unresolved_context: ContextPolicy::DefaultTo(
+                wolfram_expr::symbol::Context::system(),
+            ),
+            ..GetExprConfig::default()
+        })
     }
 
     // TODO: This needs a bit more design work before being made public. For starters,
@@ -862,91 +1795,394 @@ impl Link {
     #[doc(hidden)]
     pub fn get_expr_with_resolver(
         &mut self,
-        mut resolver: &mut dyn FnMut(&str) -> Option<Symbol>,
+        resolver: &mut dyn FnMut(&str) -> Option<Symbol>,
     ) -> Result<Expr, Error> {
-        let value = self.get_token()?;
-
-        let expr: Expr = match value {
-            Token::Integer(value) => Expr::from(value),
-            Token::Real(value) => {
-                let real: wolfram_expr::F64 = match wolfram_expr::F64::new(value) {
-                    Ok(real) => real,
-                    // TODO: Try passing a NaN value or a BigReal value through WSLINK.
-                    Err(_is_nan) => {
-                        return Err(Error::custom(format!(
-                        "NaN value passed on WSLINK cannot be used to construct an Expr"
-                    )))
-                    },
-                };
-                Expr::number(Number::Real(real))
-            },
-            Token::String(value) => Expr::string(value.as_str()),
-            Token::Symbol(value) => {
-                let symbol_str: &str = value.as_str();
-
-                // If `symbol_str` is not an absolute symbol, use the provided `resolver`
-                // to attempt to resolve it into a concrete Symbol.
-                let symbol = Symbol::try_new(symbol_str).or_else(|| resolver(symbol_str));
-
-                let symbol: Symbol = match symbol {
-                    Some(sym) => sym,
-                    None => {
-                        return Err(Error::custom(format!(
-                            "symbol name '{}' has no context",
-                            symbol_str
-                        )))
-                    },
-                };
+        self.get_expr_with_resolver_limited(resolver, Link::DEFAULT_MAX_EXPR_DEPTH)
+    }
 
-                Expr::symbol(symbol)
-            },
-            Token::Function { length: arg_count } => {
-                drop(value);
+    fn get_expr_with_resolver_limited(
+        &mut self,
+        resolver: &mut dyn FnMut(&str) -> Option<Symbol>,
+        max_depth: usize,
+    ) -> Result<Expr, Error> {
+        /// A normal expression whose head and elements are still being read.
+        struct Partial {
+            head: Option<Expr>,
+            contents: Vec<Expr>,
+            arg_count: usize,
+        }
+
+        // Normal expressions that have been started but not finished, from outermost
+        // to innermost.
+        let mut stack: Vec<Partial> = Vec::new();
+
+        loop {
+            if stack.len() >= max_depth {
+                return Err(Error::custom(format!(
+                    "expression depth exceeds maximum depth of {}",
+                    max_depth
+                )));
+            }
+
+            let value = self.get_token()?;
+
+            let mut expr: Expr = match value {
+                Token::Integer(value) => Expr::from(value),
+                Token::Real(value) => {
+                    let real: wolfram_expr::F64 = match wolfram_expr::F64::new(value) {
+                        Ok(real) => real,
+                        // TODO: Try passing a NaN value or a BigReal value through WSLINK.
+                        Err(_is_nan) => {
+                            return Err(Error::custom(
+                                "NaN value passed on WSLINK cannot be used to construct \
+                                 an Expr"
+                                    .to_owned(),
+                            ))
+                        },
+                    };
+                    Expr::number(Number::Real(real))
+                },
+                Token::String(value) => Expr::string(value.as_str()),
+                Token::Symbol(value) => {
+                    let symbol_str: &str = value.as_str();
+
+                    // If `symbol_str` is not an absolute symbol, use the provided
+                    // `resolver` to attempt to resolve it into a concrete Symbol.
+                    let symbol =
+                        Symbol::try_new(symbol_str).or_else(|| resolver(symbol_str));
+
+                    let symbol: Symbol = match symbol {
+                        Some(sym) => sym,
+                        None => return Err(Error::symbol_has_no_context(symbol_str)),
+                    };
+
+                    Expr::symbol(symbol)
+                },
+                Token::Function { length: arg_count } => {
+                    stack.push(Partial {
+                        head: None,
+                        // `arg_count` is sent by the peer, so don't trust it for
+                        // the initial allocation; `contents` grows as arguments
+                        // are actually read.
+                        contents: Vec::with_capacity(arg_count.min(1024)),
+                        arg_count,
+                    });
+                    continue;
+                },
+            };
 
-                let head = self.get_expr_with_resolver(&mut resolver)?;
+            // Add `expr` to the innermost partial expression. If that completes the
+            // partial expression, repeat with the completed expression.
+            loop {
+                let Some(partial) = stack.last_mut() else {
+                    return Ok(expr);
+                };
 
-                let mut contents = Vec::with_capacity(arg_count);
-                for _ in 0..arg_count {
-                    contents.push(self.get_expr_with_resolver(&mut resolver)?);
+                match partial.head {
+                    None => partial.head = Some(expr),
+                    Some(_) => partial.contents.push(expr),
                 }
 
-                Expr::normal(head, contents)
-            },
-        };
+                if partial.contents.len() < partial.arg_count {
+                    break;
+                }
 
-        Ok(expr)
+                let Partial {
+                    head,
+                    contents,
+                    arg_count: _,
+                } = stack.pop().unwrap();
+
+                expr = Expr::normal(head.unwrap(), contents);
+            }
+        }
     }
 
     /// Write an expression to this link.
+    ///
+    /// This function does not recurse on the structure of `expr`, so writing very deeply
+    /// nested expressions will not overflow the stack.
+    ///
+    /// See also [`Link::put_expr_limited()`].
     pub fn put_expr(&mut self, expr: &Expr) -> Result<(), Error> {
-        match expr.kind() {
-            ExprKind::Normal(normal) => {
-                self.put_raw_type(i32::from(sys::WSTKFUNC))?;
-                self.put_arg_count(normal.elements().len())?;
+        self.put_expr_impl(expr, false)
+    }
+
+    /// Write an expression to this link, writing lists of numbers as packed arrays.
+    ///
+    /// Any `List[...]` subexpression whose elements are all integers or all reals is
+    /// written using a single call to
+    /// [`WSPutInteger64Array()`](https://reference.wolfram.com/language/ref/c/WSPutInteger64Array.html)
+    /// or [`WSPutReal64Array()`](https://reference.wolfram.com/language/ref/c/WSPutReal64Array.html),
+    /// instead of writing each element individually. Other
+    /// subexpressions are written the same way as [`Link::put_expr()`].
+    ///
+    /// The expression read from the other end of the link is the same as if
+    /// [`Link::put_expr()`] had been used, but writing large numeric lists is
+    /// significantly faster.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wolfram_expr::Expr;
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// let expr = Expr::list((0..1000).map(Expr::from).collect());
+    ///
+    /// link.put_expr_fast(&expr).unwrap();
+    ///
+    /// assert_eq!(link.get_expr().unwrap(), expr);
+    /// ```
+    pub fn put_expr_fast(&mut self, expr: &Expr) -> Result<(), Error> {
+        self.put_expr_impl(expr, true)
+    }
+
+    fn put_expr_impl(&mut self, expr: &Expr, pack_lists: bool) -> Result<(), Error> {
+        // Expressions that remain to be written, in reverse order.
+        let mut stack: Vec<&Expr> = vec![expr];
+
+        while let Some(expr) = stack.pop() {
+            match expr.kind() {
+                ExprKind::Normal(normal) => {
+                    if pack_lists && self.put_packed_list(normal)? {
+                        continue;
+                    }
+
+                    self.put_next_type(TokenType::Function)?;
+                    self.put_arg_count(normal.elements().len())?;
+
+                    // Push the elements in reverse order, followed by the head, so that
+                    // the head is written first.
+                    stack.extend(normal.elements().iter().rev());
+                    stack.push(normal.head());
+                },
+                ExprKind::Symbol(symbol) => {
+                    self.put_symbol(symbol.as_str())?;
+                },
+                ExprKind::String(string) => {
+                    self.put_str(string.as_str())?;
+                },
+                ExprKind::Integer(int) => {
+                    self.put_i64(*int)?;
+                },
+                ExprKind::Real(real) => {
+                    self.put_f64(**real)?;
+                },
+            }
+        }
+
+        Ok(())
+    }
 
-                let _: () = self.put_expr(normal.head())?;
+    /// If `normal` is a non-empty list of integers or a non-empty list of reals, write it
+    /// as a packed array and return `true`. Otherwise, write nothing and return `false`.
+    fn put_packed_list(&mut self, normal: &wolfram_expr::Normal) -> Result<bool, Error> {
+        let elements = normal.elements();
 
-                for elem in normal.elements() {
-                    let _: () = self.put_expr(elem)?;
+        if elements.is_empty() || !normal.has_head(&Symbol::new("System`List")) {
+            return Ok(false);
+        }
+
+        let dims: [i32; 1] = [i32::try_from(elements.len()).map_err(|err| {
+            Error::custom(format!("list length overflows i32: {}", err))
+        })?];
+
+        // Specify the head explicitly so that the array is read back as `System`List`
+        // and not the context-less `List` symbol.
+        let mut heads: [*const c_char; 1] = ["System`List\0".as_ptr() as *const c_char];
+
+        let result = match elements[0].kind() {
+            ExprKind::Integer(_) => {
+                let data: Option<Vec<i64>> = elements
+                    .iter()
+                    .map(|elem| match elem.kind() {
+                        ExprKind::Integer(int) => Some(*int),
+                        _ => None,
+                    })
+                    .collect();
+
+                let Some(data) = data else { return Ok(false) };
+
+                unsafe {
+                    sys::WSPutInteger64Array(
+                        self.raw_link,
+                        data.as_ptr(),
+                        dims.as_ptr(),
+                        heads.as_mut_ptr(),
+                        1,
+                    )
                 }
             },
-            ExprKind::Symbol(symbol) => {
-                self.put_symbol(symbol.as_str())?;
-            },
-            ExprKind::String(string) => {
-                self.put_str(string.as_str())?;
-            },
-            ExprKind::Integer(int) => {
-                self.put_i64(*int)?;
-            },
-            ExprKind::Real(real) => {
-                self.put_f64(**real)?;
+            ExprKind::Real(_) => {
+                let data: Option<Vec<f64>> = elements
+                    .iter()
+                    .map(|elem| match elem.kind() {
+                        ExprKind::Real(real) => Some(**real),
+                        _ => None,
+                    })
+                    .collect();
+
+                let Some(data) = data else { return Ok(false) };
+
+                unsafe {
+                    sys::WSPutReal64Array(
+                        self.raw_link,
+                        data.as_ptr(),
+                        dims.as_ptr(),
+                        heads.as_mut_ptr(),
+                        1,
+                    )
+                }
             },
+            _ => return Ok(false),
+        };
+
+        if result == 0 {
+            return Err(self.error_or_unknown());
         }
 
+        Ok(true)
+    }
+
+    /// Write an expression to this link, returning an error if `expr` is nested more
+    /// than `max_depth` levels deep.
+    ///
+    /// The depth of `expr` is checked before anything is written to the link, so if an
+    /// error is returned, the link is left unchanged.
+    ///
+    /// An atomic expression has depth 1. The depth of a normal expression is one more
+    /// than the greatest depth of its head and elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wolfram_expr::Expr;
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// // {{1}}
+    /// let expr = Expr::list(vec![Expr::list(vec![Expr::from(1)])]);
+    ///
+    /// assert!(link.put_expr_limited(&expr, 2).is_err());
+    /// assert!(link.put_expr_limited(&expr, 3).is_ok());
+    /// ```
+    pub fn put_expr_limited(&mut self, expr: &Expr, max_depth: usize) -> Result<(), Error> {
+        let () = check_expr_depth(expr, max_depth)?;
+
+        self.put_expr(expr)
+    }
+
+    /// Write `expr` to this link as a complete packet, and flush the link so that the
+    /// packet is sent to the other endpoint immediately.
+    ///
+    /// This is equivalent to calling [`Link::put_expr()`], [`Link::end_packet()`], and
+    /// [`Link::flush()`] in sequence. `expr` will typically be a packet expression, such
+    /// as an [`EvaluatePacket[..]`](https://reference.wolfram.com/language/ref/EvaluatePacket.html)
+    /// sent to a Wolfram Kernel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wolfram_expr::{Expr, Symbol};
+    /// use wstp::Protocol;
+    ///
+    /// let (mut a, mut b) = wstp::channel(Protocol::IntraProcess).unwrap();
+    ///
+    /// let packet = Expr::normal(
+    ///     Symbol::new("System`EvaluatePacket"),
+    ///     vec![Expr::from(5)],
+    /// );
+    ///
+    /// a.send_expr(&packet).unwrap();
+    ///
+    /// assert_eq!(b.get_expr(), Ok(packet));
+    /// ```
+    pub fn send_expr(&mut self, expr: &Expr) -> Result<(), Error> {
+        let () = self.put_expr(expr)?;
+        let () = self.send_packet()?;
+
+        Ok(())
+    }
+
+    /// Mark the end of the packet currently being written, and flush the link so that
+    /// the packet is sent to the other endpoint immediately.
+    ///
+    /// This is equivalent to calling [`Link::end_packet()`] followed by
+    /// [`Link::flush()`]:
+    ///
+    /// * [`Link::end_packet()`] marks where the current packet ends, but does not send
+    ///   any data. Data written to a link is buffered, and the other endpoint may wait
+    ///   indefinitely for a packet that has been ended but not flushed.
+    /// * [`Link::flush()`] sends any buffered data, but does not mark the end of a
+    ///   packet. A Wolfram Kernel reading from the other endpoint will wait for the end
+    ///   of the packet before evaluating it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Protocol;
+    ///
+    /// let (mut a, mut b) = wstp::channel(Protocol::IntraProcess).unwrap();
+    ///
+    /// a.put_function("System`EvaluatePacket", 1).unwrap();
+    /// a.put_function("System`Plus", 2).unwrap();
+    /// a.put_i64(1).unwrap();
+    /// a.put_i64(1).unwrap();
+    /// a.send_packet().unwrap();
+    ///
+    /// assert_eq!(b.test_head("System`EvaluatePacket"), Ok(1));
+    /// ```
+    pub fn send_packet(&mut self) -> Result<(), Error> {
+        let () = self.end_packet()?;
+        let () = self.flush()?;
+
         Ok(())
     }
 
+    /// Read the contents of the next packet on this link, discarding the packet head
+    /// and anything left in the packet after the first expression.
+    ///
+    /// This is equivalent to calling [`Link::raw_next_packet()`], [`Link::get_expr()`],
+    /// and [`Link::new_packet()`] in sequence. Afterwards, the link is positioned at the
+    /// start of the following packet, ready for the next call to `receive_expr()`.
+    ///
+    /// This function is intended for use with full-duplex links carrying packets, like
+    /// a link to a Wolfram Kernel, where the expression read is e.g. the contents of a
+    /// [`ReturnPacket[..]`](https://reference.wolfram.com/language/ref/ReturnPacket.html).
+    /// It is not intended for loopback links used as expression buffers. Use
+    /// [`Link::raw_next_packet()`] directly if the type of the packet is needed.
+    ///
+    /// See also [`Link::send_expr()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wolfram_expr::Expr;
+    /// use wstp::Protocol;
+    ///
+    /// let (mut a, mut b) = wstp::channel(Protocol::IntraProcess).unwrap();
+    ///
+    /// // ReturnPacket[5]
+    /// a.put_function("ReturnPacket", 1).unwrap();
+    /// a.put_i64(5).unwrap();
+    /// a.end_packet().unwrap();
+    /// a.flush().unwrap();
+    ///
+    /// assert_eq!(b.receive_expr(), Ok(Expr::from(5)));
+    /// ```
+    pub fn receive_expr(&mut self) -> Result<Expr, Error> {
+        let _: i32 = self.raw_next_packet()?;
+
+        let expr = self.get_expr()?;
+
+        let () = self.new_packet()?;
+
+        Ok(expr)
+    }
+
     /// Transfer an expression from this link to another.
     ///
     /// # Example
@@ -976,6 +2212,8 @@ impl Link {
             return Err(self.error_or_unknown());
         }
 
+        link_state::element_read(self.raw_link);
+
         Ok(())
     }
 
@@ -1007,32 +2245,408 @@ impl Link {
             };
         }
 
+        link_state::reset_reads(self.raw_link);
+
         Ok(())
     }
+
+    /// Count the number of complete expressions waiting to be read from this loopback
+    /// link, without consuming them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_i64(1).unwrap();
+    /// link.put_function("System`List", 2).unwrap();
+    /// link.put_i64(2).unwrap();
+    /// link.put_i64(3).unwrap();
+    ///
+    /// assert_eq!(link.peek_count(), Ok(2));
+    ///
+    /// // The expressions are still available to read.
+    /// assert_eq!(link.get_i64(), Ok(1));
+    /// assert_eq!(link.peek_count(), Ok(1));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `!self.is_loopback()`.
+    pub fn peek_count(&mut self) -> Result<usize, Error> {
+        if !self.is_loopback() {
+            panic!("peek_count(): self must be a loopback link");
+        }
+
+        self.peek(|link| {
+            let mut count = 0;
+
+            while link.is_ready() {
+                let () = link.skip_exprs(1)?;
+                count += 1;
+            }
+
+            Ok(count)
+        })?
+    }
+
+    /// Copy every complete expression waiting to be read from this loopback link to
+    /// `dest`, without consuming them.
+    ///
+    /// Unlike [`Link::transfer_to_end_of_loopback_link()`], the contents of this link
+    /// are left intact, so they can be copied to several destinations, or read later.
+    ///
+    /// This is implemented using a WSTP mark: a mark is created at the current read
+    /// position, the expressions are transferred to `dest`, and then this link is
+    /// rewound to the mark. Only data after the current read position is copied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut source = Link::new_loopback().unwrap();
+    /// source.put_i64(1).unwrap();
+    /// source.put_str("two").unwrap();
+    ///
+    /// let mut a = Link::new_loopback().unwrap();
+    /// let mut b = Link::new_loopback().unwrap();
+    ///
+    /// source.copy_loopback_to(&mut a).unwrap();
+    /// source.copy_loopback_to(&mut b).unwrap();
+    ///
+    /// assert_eq!(a.get_i64(), Ok(1));
+    /// assert_eq!(b.get_i64(), Ok(1));
+    ///
+    /// // `source` still contains both expressions.
+    /// assert_eq!(source.peek_count(), Ok(2));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `!self.is_loopback()`.
+    pub fn copy_loopback_to(&mut self, dest: &mut Link) -> Result<(), Error> {
+        if !self.is_loopback() {
+            panic!("copy_loopback_to(): self must be a loopback link");
+        }
+
+        self.peek(|link| {
+            while link.is_ready() {
+                let () = link.transfer_expr_to(dest)?;
+            }
+
+            Ok(())
+        })?
+    }
+
+    /// Create a new loopback link containing a copy of every complete expression
+    /// waiting to be read from this loopback link, without consuming them.
+    ///
+    /// The returned link is independent of this link: reading from either one does not
+    /// affect the read position or contents of the other. This makes it possible to
+    /// serialize an expression once, and then read it many times.
+    ///
+    /// This function takes `&mut self` because the contents are copied using
+    /// [`Link::copy_loopback_to()`], which temporarily moves the read position of this
+    /// link.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut cached = Link::new_loopback().unwrap();
+    /// cached.put_str("expensive").unwrap();
+    ///
+    /// for _ in 0..3 {
+    ///     let mut snapshot = cached.snapshot_loopback().unwrap();
+    ///
+    ///     assert_eq!(snapshot.get_string().unwrap(), "expensive");
+    /// }
+    ///
+    /// assert_eq!(cached.get_string().unwrap(), "expensive");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `!self.is_loopback()`.
+    pub fn snapshot_loopback(&mut self) -> Result<Link, Error> {
+        if !self.is_loopback() {
+            panic!("snapshot_loopback(): self must be a loopback link");
+        }
+
+        let mut snapshot = Link::new_loopback()?;
+
+        let () = self.copy_loopback_to(&mut snapshot)?;
+
+        Ok(snapshot)
+    }
+
+    /// Check whether this loopback link and `other` contain structurally equal
+    /// expressions, without consuming them.
+    ///
+    /// Every complete expression waiting to be read from each link is read using
+    /// [`Link::get_all_exprs()`], and the two sequences are compared. Both links are
+    /// then rewound to their original read positions using WSTP marks, so their contents
+    /// remain available to read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut a = Link::new_loopback().unwrap();
+    /// let mut b = Link::new_loopback().unwrap();
+    ///
+    /// a.put_i64(1).unwrap();
+    /// b.put_i64(1).unwrap();
+    ///
+    /// assert_eq!(a.content_eq(&mut b), Ok(true));
+    ///
+    /// b.put_i64(2).unwrap();
+    ///
+    /// assert_eq!(a.content_eq(&mut b), Ok(false));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if either link is not a loopback link.
+    pub fn content_eq(&mut self, other: &mut Link) -> Result<bool, Error> {
+        if !self.is_loopback() || !other.is_loopback() {
+            panic!("content_eq(): both links must be loopback links");
+        }
+
+        let ours: Vec<Expr> = self.peek(|link| link.get_all_exprs())??;
+        let theirs: Vec<Expr> = other.peek(|link| link.get_all_exprs())??;
+
+        Ok(ours == theirs)
+    }
+
+    /// Read and discard every complete expression that is currently available to read
+    /// from this link, returning the number of expressions discarded.
+    ///
+    /// This function stops as soon as [`Link::is_ready()`] returns `false`; it does not
+    /// wait for more data to arrive. This can be used to return a long-lived link to a
+    /// clean state, e.g. after an error occurred partway through a conversation with the
+    /// other endpoint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_i64(1).unwrap();
+    /// link.put_str("two").unwrap();
+    ///
+    /// assert_eq!(link.drain(), Ok(2));
+    /// assert!(!link.is_ready());
+    /// ```
+    pub fn drain(&mut self) -> Result<usize, Error> {
+        // Expressions are transferred onto a scratch loopback link instead of being
+        // read using `get_expr()`, so that arbitrarily deep expressions can be
+        // discarded without constructing them.
+        let mut scratch = Link::new_loopback()?;
+
+        let mut count = 0;
+
+        while self.is_ready() {
+            let () = self.transfer_expr_to(&mut scratch)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Read every complete expression that is currently available to read from this
+    /// link, and format them as a string, one expression per line.
+    ///
+    /// **This function consumes the contents of the link.** The expressions that are
+    /// formatted are no longer available to be read afterwards. Like
+    /// [`Link::drain()`], this function stops as soon as [`Link::is_ready()`] returns
+    /// `false`.
+    ///
+    /// This is intended for use in tests and when debugging.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_i64(1).unwrap();
+    /// link.put_function("System`List", 2).unwrap();
+    /// link.put_str("a").unwrap();
+    /// link.put_symbol("Global`b").unwrap();
+    ///
+    /// assert_eq!(
+    ///     link.dump_remaining().unwrap(),
+    ///     "1\nSystem`List[\"a\", Global`b]"
+    /// );
+    /// assert!(!link.is_ready());
+    /// ```
+    pub fn dump_remaining(&mut self) -> Result<String, Error> {
+        let mut lines: Vec<String> = Vec::new();
+
+        while self.is_ready() {
+            lines.push(self.get_expr()?.to_string());
+        }
+
+        Ok(lines.join("\n"))
+    }
 }
 
 //======================================
 // Utilities
 //======================================
 
-fn for_each_addr<T, F>(addrs: Vec<net::SocketAddr>, mut func: F) -> Result<T, Error>
+/// Resolve `addr`, and call `func` on each of the resolved addresses, returning the
+/// first successful result.
+///
+/// `resolve_error` is used to describe the error returned if `addr` cannot be resolved.
+pub(crate) fn for_each_addr<A, T, F>(
+    addr: A,
+    resolve_error: &str,
+    mut func: F,
+) -> Result<T, Error>
 where
+    A: net::ToSocketAddrs,
     F: FnMut(net::SocketAddr) -> Result<T, Error>,
 {
-    let mut last_error = None;
+    let input: Option<String> = describe_addr_spec(&addr);
+
+    let addrs = addr.to_socket_addrs().map_err(|err| match &input {
+        Some(input) => Error::custom(format!("{} {}: {}", resolve_error, input, err)),
+        None => Error::custom(format!("{}: {}", resolve_error, err)),
+    })?;
+
+    let mut errors: Vec<(net::SocketAddr, Error)> = Vec::new();
 
     for addr in addrs {
         match func(addr) {
             Ok(result) => return Ok(result),
-            Err(err) => last_error = Some(err),
+            Err(err) => errors.push((addr, err)),
+        }
+    }
+
+    let code = match errors.last() {
+        Some((_, last_error)) => last_error.code,
+        None => {
+            return Err(Error::custom(format!(
+                "socket address list is empty: {} did not resolve to any socket \
+                 addresses",
+                input.as_deref().unwrap_or("the address")
+            )))
+        },
+    };
+
+    // Describe every address that was tried, not just the last one, so that failures
+    // connecting to multi-homed hosts can be diagnosed.
+    let attempts: Vec<String> = errors
+        .iter()
+        .map(|(addr, err)| format!("{} ({})", addr, err.message))
+        .collect();
+
+    Err(Error {
+        code,
+        message: format!("tried {}", attempts.join(", ")),
+        end_of_data: false,
+        timed_out: false,
+    })
+}
+
+/// Describe `addr` if it is a host name and port, like `"example.com:8080"` or
+/// `("example.com", 8080)`.
+///
+/// The public functions that resolve addresses do not require `A` to implement
+/// [`fmt::Debug`], so only the string forms are described, by checking the type of `A`.
+fn describe_addr_spec<A>(addr: &A) -> Option<String> {
+    /// Reinterpret `value` as a `T` if `A` and `T` are the same type, ignoring
+    /// lifetimes.
+    fn cast<A, T: 'static>(value: &A) -> Option<&T> {
+        if non_static_type_id::<A>() != std::any::TypeId::of::<T>() {
+            return None;
+        }
+
+        // SAFETY: `A` and `T` are the same type, up to lifetimes. The returned
+        //         reference is only used to format the value before `value` is
+        //         dropped.
+        Some(unsafe { &*(value as *const A as *const T) })
+    }
+
+    if let Some(spec) = cast::<A, &str>(addr) {
+        Some(spec.to_string())
+    } else if let Some(spec) = cast::<A, String>(addr) {
+        Some(spec.clone())
+    } else if let Some(spec) = cast::<A, &String>(addr) {
+        Some(spec.to_string())
+    } else if let Some((host, port)) = cast::<A, (&str, u16)>(addr) {
+        Some(format!("{}:{}", host, port))
+    } else if let Some((host, port)) = cast::<A, (String, u16)>(addr) {
+        Some(format!("{}:{}", host, port))
+    } else {
+        None
+    }
+}
+
+/// Get the [`TypeId`][std::any::TypeId] of `T`, with any lifetimes in `T` treated as
+/// `'static`.
+fn non_static_type_id<T>() -> std::any::TypeId {
+    trait NonStaticAny {
+        fn get_type_id(&self) -> std::any::TypeId
+        where
+            Self: 'static;
+    }
+
+    impl<T> NonStaticAny for std::marker::PhantomData<T> {
+        fn get_type_id(&self) -> std::any::TypeId
+        where
+            Self: 'static,
+        {
+            std::any::TypeId::of::<T>()
         }
     }
 
-    Err(last_error
-        .unwrap_or_else(|| Error::custom(format!("socket address list is empty"))))
+    let phantom = std::marker::PhantomData::<T>;
+
+    // SAFETY: Only the lifetime of the trait object is changed. `get_type_id()` does
+    //         not use the value, so it cannot observe a dangling lifetime.
+    let phantom: &(dyn NonStaticAny + 'static) =
+        unsafe { std::mem::transmute::<&dyn NonStaticAny, _>(&phantom) };
+
+    phantom.get_type_id()
 }
 
-/// Construct an address string in the special syntax used by WSTP.
+/// Check that `expr` is not nested more than `max_depth` levels deep.
+///
+/// This function does not recurse on the structure of `expr`.
+fn check_expr_depth(expr: &Expr, max_depth: usize) -> Result<(), Error> {
+    let mut stack: Vec<(&Expr, usize)> = vec![(expr, 1)];
+
+    while let Some((expr, depth)) = stack.pop() {
+        if depth > max_depth {
+            return Err(Error::custom(format!(
+                "expression depth exceeds maximum depth of {}",
+                max_depth
+            )));
+        }
+
+        if let ExprKind::Normal(normal) = expr.kind() {
+            stack.push((normal.head(), depth + 1));
+            stack.extend(normal.elements().iter().map(|elem| (elem, depth + 1)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Construct a `port@host` TCPIP link name for `addr`.
+///
+/// For listening links, WSTP interprets `host` as the interface to bind to.
 fn tcpip_link_name(addr: &net::SocketAddr) -> String {
     format!("{}@{}", addr.port(), addr.ip())
 }
@@ -1053,6 +2667,55 @@ impl Display for Protocol {
     }
 }
 
+/// Human-readable summary of the state of a [`Link`], intended for logging.
+///
+/// The link protocol is included for links created using [`Link::listen()`] or
+/// [`Link::connect()`]. Use the [`Debug`][fmt::Debug] implementation to see the raw
+/// `WSLINK` pointer.
+///
+/// # Example
+///
+/// ```
+/// use wstp::{Link, Protocol};
+///
+/// let mut link = Link::new_loopback().unwrap();
+///
+/// link.put_i64(1).unwrap();
+///
+/// assert_eq!(
+///     link.to_string(),
+///     format!("Link(name={:?}, ready=true, loopback=true)", link.link_name())
+/// );
+///
+/// let listener = Link::listen(Protocol::IntraProcess, "").unwrap();
+///
+/// assert!(listener.to_string().starts_with("Link(IntraProcess, name="));
+/// ```
+//
+// NOTE: WSTP does not provide a supported way to query the protocol of an existing
+//       link, so the protocol is only known for links created by `Link::listen()` or
+//       `Link::connect()`, which record it.
+impl Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let protocol: Option<Protocol> =
+            link_state::with(self.raw_link, |state| state.protocol.clone());
+
+        write!(f, "Link(")?;
+
+        if let Some(protocol) = protocol {
+            write!(f, "{}, ", protocol)?;
+        }
+
+        write!(f, "name={:?}, ", self.link_name())?;
+
+        if let Some(description) = self.description() {
+            write!(f, "description={:?}, ", description)?;
+        }
+
+        write!(f, "ready={}, loopback={})", self.is_ready(), self.is_loopback())
+    }
+}
+
 //======================================
 // Drop impls
 //======================================
@@ -1061,6 +2724,10 @@ impl Drop for Link {
     fn drop(&mut self) {
         let Link { raw_link } = *self;
 
+        link_state::remove(raw_link);
+
+        // Note: WSClose() does not report errors, so there are none to surface here.
+        //       `Link::close_checked()` can be used to detect unsent data.
         unsafe {
             sys::WSClose(raw_link);
         }
@@ -42,12 +42,8 @@ impl LinkServer {
     /// let server = LinkServer::bind("127.0.0.1:8080").unwrap();
     /// ```
     pub fn bind<A: std::net::ToSocketAddrs>(addrs: A) -> Result<Self, Error> {
-        let addrs = addrs.to_socket_addrs().map_err(|err| {
-            Error::custom(format!("error binding LinkServer to address: {}", err))
-        })?;
-
         // Try each address, returning the first one which binds successfully.
-        crate::for_each_addr(addrs.collect(), |addr| {
+        crate::for_each_addr(addrs, "error binding LinkServer to address", |addr| {
             let mut err: std::os::raw::c_int = sys::MLEOK;
 
             let iface = CString::new(addr.ip().to_string())
@@ -123,6 +119,25 @@ impl LinkServer {
     /// Note that the reasoning for the `Send` and `'static` constraints is similiar to
     /// that for [`std::thread::spawn()`], whose documentation may be a useful
     /// additional reference.
+    ///
+    /// # Errors and long-running callbacks
+    ///
+    /// WSTP only calls `callback` when a connection has been accepted successfully. If
+    /// accepting a connection fails on the background thread, WSTP does not report the
+    /// error, so there is no way for this function to provide an error callback. Use
+    /// [`LinkServer::accept()`] on a thread of your own if accept errors must be
+    /// observed.
+    ///
+    /// `callback` is called on the link server's background thread, so a callback that
+    /// blocks may delay the handling of further connections. It is not possible for the
+    /// link server to safely interrupt a callback that runs for too long, because the
+    /// callback owns the [`Link`] it was given. To bound the time spent handling a
+    /// connection, the callback should either use time-limited operations like
+    /// [`Link::activate_timeout()`] and [`Link::wait_ready()`], or move the link to
+    /// another thread and return immediately.
+    ///
+    /// If `callback` panics, the link it was given is closed and the link server
+    /// continues accepting new connections.
     pub fn new_with_callback<F>(port: u16, callback: F) -> Result<Self, Error>
     where
         F: FnMut(Link) + Send + Sync + 'static,
@@ -249,6 +264,11 @@ impl LinkServer {
     /// Use [`LinkServer::new_with_callback()`] to create a link server which accepts
     /// connections asyncronously via a callback function.
     ///
+    /// Note that there is no variant of this method that accepts a [`Timeout`][crate::Timeout]:
+    /// WSTP does not provide a way to cancel a blocked call to
+    /// `WSWaitForNewLinkFromLinkServer()`, short of shutting down the link server. If a
+    /// bounded wait is required, use [`LinkServer::new_with_callback()`] instead.
+    ///
     /// *WSTP C API Documentation:* [`WSWaitForNewLinkFromLinkServer`](https://reference.wolfram.com/language/ref/c/WSWaitForNewLinkFromLinkServer.html)
     pub fn accept(&self) -> Result<Link, Error> {
         let mut err: c_int = sys::MLEOK;
@@ -301,9 +321,14 @@ extern "C" fn callback_trampoline<F: FnMut(Link) + Send + Sync + 'static>(
         link = Link::unchecked_new(raw_link);
     }
 
-    // Call the closure provided by the user
-    // FIXME: Catch panic's in the user's code to prevent unwinding over C stack frames.
-    user_closure(link);
+    // Call the closure provided by the user.
+    //
+    // Catch any panics which result from `user_closure()` to prevent unwinding over C
+    // stack frames. If a panic occurs, `link` is dropped (closing it) and the link
+    // server continues accepting new connections. The panic message will already have
+    // been printed by the panic hook.
+    let _: Result<(), _> =
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| user_closure(link)));
 }
 
 impl Drop for LinkServer {
@@ -0,0 +1,196 @@
+//! Per-link state maintained by this crate.
+//!
+//! [`Link`] is a `#[repr(transparent)]` wrapper around a `WSLINK`, so that a borrowed
+//! `&mut WSLINK` can be used as a `&mut Link` (see [`Link::unchecked_ref_cast_mut()`]).
+//! Settings that WSTP does not store for a link are kept in a global map instead,
+//! keyed by the address of the link's `WSLINK`.
+//!
+//! Because a `WSLINK` allocated after another has been closed may have the same
+//! address, the entry for a `WSLINK` is removed:
+//!
+//! * when the [`Link`] that owns it is dropped,
+//! * when a new [`Link`] takes ownership of a `WSLINK`, in [`Link::unchecked_new()`],
+//! * and when [`Link::with_borrowed()`] returns, which restores the settings the
+//!   borrowed link had before it was called.
+//!
+//! Links borrowed using [`Link::unchecked_ref_cast_mut()`] are never dropped, so state
+//! set on them is only removed by one of the above.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use crate::{sys::WSLINK, LinkStats, Protocol};
+
+/// State of a single link.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct LinkState {
+    /// See [`Link::set_max_array_elements()`].
+    pub max_array_elements: Option<usize>,
+    /// See [`Link::set_description()`].
+    pub description: Option<String>,
+    /// `true` if the link was opened by [`Link::open_with_args()`] and has not been
+    /// activated. See [`Link::is_activated()`].
+    pub unactivated: bool,
+    /// The protocol of a link created by [`Link::listen()`] or [`Link::connect()`].
+    pub protocol: Option<Protocol>,
+    /// `true` if the link was created by [`Link::listen()`]. See
+    /// [`Link::is_peer_present()`].
+    pub listening: bool,
+    /// Number of elements that remain to be read from each function that is currently
+    /// being read, innermost last. See [`Link::read_depth()`].
+    pub read_stack: Vec<usize>,
+    /// See [`Link::stats()`].
+    pub stats: LinkStats,
+}
+
+static LINK_STATE: OnceLock<Mutex<HashMap<usize, LinkState>>> = OnceLock::new();
+
+/// Number of links with a non-empty [`LinkState::read_stack`].
+///
+/// Reading an element only changes the read depth of a link that is currently reading
+/// a function, so this avoids taking the lock on `LINK_STATE` for every element read
+/// in the common case that no link is.
+static READING_FUNCTIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// `false` if [`LINK_STATE`] is known to be empty.
+///
+/// This avoids taking the lock on `LINK_STATE` for every link operation that reads the
+/// state of a link in the common case that no link has any state.
+static HAS_ENTRIES: AtomicBool = AtomicBool::new(false);
+
+fn get_link_state_lock() -> MutexGuard<'static, HashMap<usize, LinkState>> {
+    LINK_STATE
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .expect("failed to acquire lock on LINK_STATE")
+}
+
+/// Call `func` with the state of `raw_link`.
+pub(crate) fn with<T, F: FnOnce(&LinkState) -> T>(raw_link: WSLINK, func: F) -> T {
+    if !HAS_ENTRIES.load(Ordering::Acquire) {
+        return func(&LinkState::default());
+    }
+
+    match get_link_state_lock().get(&(raw_link as usize)) {
+        Some(state) => func(state),
+        None => func(&LinkState::default()),
+    }
+}
+
+/// Call `func` to modify the state of `raw_link`.
+pub(crate) fn update<F: FnOnce(&mut LinkState)>(raw_link: WSLINK, func: F) {
+    let mut map = get_link_state_lock();
+
+    let state = map.entry(raw_link as usize).or_default();
+
+    let was_reading = !state.read_stack.is_empty();
+
+    func(state);
+
+    match (was_reading, !state.read_stack.is_empty()) {
+        (false, true) => {
+            READING_FUNCTIONS.fetch_add(1, Ordering::AcqRel);
+        },
+        (true, false) => {
+            READING_FUNCTIONS.fetch_sub(1, Ordering::AcqRel);
+        },
+        (false, false) | (true, true) => (),
+    }
+
+    // Don't keep entries for links that have the default state.
+    if *state == LinkState::default() {
+        map.remove(&(raw_link as usize));
+    }
+
+    HAS_ENTRIES.store(!map.is_empty(), Ordering::Release);
+}
+
+/// Get a copy of the state of `raw_link`, to be restored later using [`restore()`].
+pub(crate) fn save(raw_link: WSLINK) -> LinkState {
+    with(raw_link, LinkState::clone)
+}
+
+/// Restore the settings of `raw_link` saved by [`save()`].
+///
+/// Only settings made by the user of a link are restored. The fields that record the
+/// state of the `WSLINK` itself, like whether it has been activated, are kept.
+pub(crate) fn restore(raw_link: WSLINK, saved: LinkState) {
+    let LinkState {
+        max_array_elements,
+        description,
+        unactivated: _,
+        protocol: _,
+        listening: _,
+        read_stack: _,
+        stats: _,
+    } = saved;
+
+    update(raw_link, |state| {
+        state.max_array_elements = max_array_elements;
+        state.description = description;
+    })
+}
+
+/// Remove all state of `raw_link`.
+pub(crate) fn remove(raw_link: WSLINK) {
+    if !HAS_ENTRIES.load(Ordering::Acquire) {
+        return;
+    }
+
+    let mut map = get_link_state_lock();
+
+    if let Some(state) = map.remove(&(raw_link as usize)) {
+        if !state.read_stack.is_empty() {
+            READING_FUNCTIONS.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+
+    HAS_ENTRIES.store(!map.is_empty(), Ordering::Release);
+}
+
+//======================================
+// Read depth
+//======================================
+
+/// Record that a function with `elements` elements remaining to be read was entered
+/// on `raw_link`.
+pub(crate) fn enter_function(raw_link: WSLINK, elements: usize) {
+    if elements == 0 {
+        // The function is already complete.
+        element_read(raw_link);
+        return;
+    }
+
+    update(raw_link, |state| state.read_stack.push(elements))
+}
+
+/// Record that a complete element was read from `raw_link`.
+pub(crate) fn element_read(raw_link: WSLINK) {
+    if READING_FUNCTIONS.load(Ordering::Acquire) == 0 {
+        return;
+    }
+
+    update(raw_link, |state| {
+        // Reading the last element of a function completes an element of the function
+        // that contains it.
+        while let Some(remaining) = state.read_stack.last_mut() {
+            *remaining -= 1;
+
+            if *remaining > 0 {
+                break;
+            }
+
+            state.read_stack.pop();
+        }
+    })
+}
+
+/// Record that `raw_link` has moved to the start of a new packet.
+pub(crate) fn reset_reads(raw_link: WSLINK) {
+    if READING_FUNCTIONS.load(Ordering::Acquire) == 0 {
+        return;
+    }
+
+    update(raw_link, |state| state.read_stack.clear())
+}
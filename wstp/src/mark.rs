@@ -0,0 +1,88 @@
+//! Private helpers for reading ahead on a [`Link`] using WSTP marks.
+//!
+//! A mark records a position in the stream of incoming data on a link. Seeking back to
+//! a mark makes it possible to re-read data that has already been read, which is used
+//! to implement operations that need to inspect an expression before deciding how to
+//! read it.
+//!
+//! *WSTP C API Documentation:* [`WSCreateMark()`](https://reference.wolfram.com/language/ref/c/WSCreateMark.html)
+
+use std::os::raw::c_int;
+
+use crate::{link_state, sys, Error, Link};
+
+impl Link {
+    /// Call `func`, and then rewind this link to the position it was at before `func`
+    /// was called.
+    ///
+    /// Any data read from this link by `func` will be available to read again after
+    /// this function returns.
+    pub(crate) fn peek<T, F>(&mut self, func: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Link) -> T,
+    {
+        let mark = self.create_mark()?;
+
+        let read_stack: Vec<usize> =
+            link_state::with(self.raw_link, |state| state.read_stack.clone());
+
+        let result = func(self);
+
+        let seek_result = self.seek_to_mark(mark, 0);
+
+        unsafe { sys::WSDestroyMark(self.raw_link, mark) };
+
+        // Data read by `func` will be read again, so it doesn't change the read depth.
+        if link_state::with(self.raw_link, |state| state.read_stack != read_stack) {
+            link_state::update(self.raw_link, |state| state.read_stack = read_stack);
+        }
+
+        let () = seek_result?;
+
+        Ok(result)
+    }
+
+    /// Skip over the next `count` complete expressions on this link.
+    pub(crate) fn skip_exprs(&mut self, count: usize) -> Result<(), Error> {
+        let mark = self.create_mark()?;
+
+        let seek_result = self.seek_to_mark(mark, count);
+
+        unsafe { sys::WSDestroyMark(self.raw_link, mark) };
+
+        let () = seek_result?;
+
+        for _ in 0..count {
+            link_state::element_read(self.raw_link);
+        }
+
+        Ok(())
+    }
+
+    fn create_mark(&mut self) -> Result<sys::WSMARK, Error> {
+        let mark: sys::WSMARK = unsafe { sys::WSCreateMark(self.raw_link) };
+
+        if mark.is_null() {
+            return Err(self.error_or_unknown());
+        }
+
+        Ok(mark)
+    }
+
+    /// Seek to the position `index` expressions after `mark`.
+    ///
+    /// *WSTP C API Documentation:* [`WSSeekToMark()`](https://reference.wolfram.com/language/ref/c/WSSeekToMark.html)
+    fn seek_to_mark(&mut self, mark: sys::WSMARK, index: usize) -> Result<(), Error> {
+        let index = c_int::try_from(index).map_err(|err| {
+            Error::custom(format!("seek_to_mark: index overflows c_int: {}", err))
+        })?;
+
+        let result = unsafe { sys::WSSeekToMark(self.raw_link, mark, index) };
+
+        if result.is_null() {
+            return Err(self.error_or_unknown());
+        }
+
+        Ok(())
+    }
+}
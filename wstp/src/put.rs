@@ -7,12 +7,12 @@ use crate::{
         WSPutInteger8, WSPutReal32, WSPutReal64, WSPutUTF16String, WSPutUTF32String,
         WSPutUTF8String, WSPutUTF8Symbol,
     },
-    Error, Link,
+    get, link_state, ArrayOrder, Error, Link, TokenType,
 };
 
 impl Link {
-    /// TODO: Augment this function with a `put_type()` method which takes a
-    ///       (non-exhaustive) enum value.
+    /// See also [`Link::put_next_type()`], which takes a [`TokenType`] instead of a raw
+    /// WSTP token type constant.
     ///
     /// *WSTP C API Documentation:* [`WSPutType()`](https://reference.wolfram.com/language/ref/c/WSPutType.html)
     pub fn put_raw_type(&mut self, type_: i32) -> Result<(), Error> {
@@ -23,12 +23,54 @@ impl Link {
         Ok(())
     }
 
+    /// Specify the type of the next token that will be written to this link.
+    ///
+    /// This is a typed wrapper around [`Link::put_raw_type()`].
+    ///
+    /// # Example
+    ///
+    /// Write the function `Sin[1.0]` one part at a time:
+    ///
+    /// ```
+    /// use wstp::{Link, TokenType};
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_next_type(TokenType::Function).unwrap();
+    /// link.put_arg_count(1).unwrap();
+    /// link.put_symbol("System`Sin").unwrap();
+    /// link.put_f64(1.0).unwrap();
+    ///
+    /// assert_eq!(link.test_head("System`Sin"), Ok(1));
+    /// assert_eq!(link.get_f64(), Ok(1.0));
+    /// ```
+    ///
+    /// *WSTP C API Documentation:* [`WSPutType()`](https://reference.wolfram.com/language/ref/c/WSPutType.html)
+    pub fn put_next_type(&mut self, type_: TokenType) -> Result<(), Error> {
+        let raw_type: u8 = match type_ {
+            TokenType::Integer => sys::WSTKINT,
+            TokenType::Real => sys::WSTKREAL,
+            TokenType::Symbol => sys::WSTKSYM,
+            TokenType::String => sys::WSTKSTR,
+            TokenType::Function => sys::WSTKFUNC,
+        };
+
+        self.put_raw_type(i32::from(raw_type))
+    }
+
+    /// Mark the end of the packet currently being written to this link.
+    ///
+    /// This does not send the packet: call [`Link::flush()`] afterwards, or use
+    /// [`Link::send_packet()`] to do both.
+    ///
     /// *WSTP C API Documentation:* [`WSEndPacket()`](https://reference.wolfram.com/language/ref/c/WSEndPacket.html)
     pub fn end_packet(&mut self) -> Result<(), Error> {
         if unsafe { sys::WSEndPacket(self.raw_link) } == 0 {
             return Err(self.error_or_unknown());
         }
 
+        link_state::update(self.raw_link, |state| state.stats.packets_sent += 1);
+
         Ok(())
     }
 
@@ -114,6 +156,15 @@ impl Link {
         Ok(())
     }
 
+    /// Put a string containing the single character `c`.
+    ///
+    /// Use [`Link::get_char()`] to read the character.
+    pub fn put_char(&mut self, c: char) -> Result<(), Error> {
+        let mut buffer = [0; 4];
+
+        self.put_str(c.encode_utf8(&mut buffer))
+    }
+
     //==================================
     // Functions
     //==================================
@@ -162,7 +213,7 @@ impl Link {
         head: H,
         count: usize,
     ) -> Result<(), Error> {
-        self.put_raw_type(i32::from(sys::WSTKFUNC))?;
+        self.put_next_type(TokenType::Function)?;
         self.put_arg_count(count)?;
 
         if let Some(head) = head.into() {
@@ -188,6 +239,251 @@ impl Link {
         Ok(())
     }
 
+    //==================================
+    // Lists
+    //==================================
+
+    /// Put the empty list `{}` onto this link.
+    ///
+    /// This is equivalent to `put_function("System`List", 0)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::{FromLink, Link};
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_empty_list().unwrap();
+    ///
+    /// assert_eq!(Vec::<i64>::from_link(&mut link), Ok(vec![]));
+    /// ```
+    pub fn put_empty_list(&mut self) -> Result<(), Error> {
+        self.put_function("System`List", 0)
+    }
+
+    /// Put a list of lists onto this link, using `put_elem` to write each element.
+    ///
+    /// Each row is written as a separate `List[..]` expression, so the rows do not
+    /// need to have the same length. Use [`Link::put_i64_array()`] and related
+    /// functions to write rectangular numeric data as a packed array.
+    ///
+    /// # Example
+    ///
+    /// Put the expression `{{1, 2, 3}, {}, {4}}` on the link:
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// let rows: Vec<Vec<i64>> = vec![vec![1, 2, 3], vec![], vec![4]];
+    ///
+    /// link.put_nested_list(&rows, |link, &value| link.put_i64(value))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     link.get_expr().unwrap().to_string(),
+    ///     "System`List[System`List[1, 2, 3], System`List[], System`List[4]]"
+    /// );
+    /// ```
+    pub fn put_nested_list<I, R, F>(&mut self, rows: I, mut put_elem: F) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = R>,
+        I::IntoIter: ExactSizeIterator,
+        R: IntoIterator,
+        R::IntoIter: ExactSizeIterator,
+        F: FnMut(&mut Link, R::Item) -> Result<(), Error>,
+    {
+        let rows = rows.into_iter();
+
+        self.put_function("System`List", rows.len())?;
+
+        for row in rows {
+            let row = row.into_iter();
+
+            self.put_function("System`List", row.len())?;
+
+            for elem in row {
+                put_elem(self, elem)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    //==================================
+    // Associations
+    //==================================
+
+    /// Put an [`Association`](https://reference.wolfram.com/language/ref/Association.html)
+    /// onto this link, using `put_key` and `put_value` to write each key and value.
+    ///
+    /// Each `(key, value)` entry is written as a `key -> value` rule, in the order the
+    /// entries are yielded by `entries`. Pass a [`BTreeMap`][std::collections::BTreeMap]
+    /// to write the keys in sorted order, e.g. when deterministic output is needed.
+    ///
+    /// See also [`Link::get_association()`].
+    ///
+    /// # Example
+    ///
+    /// Put the expression `<|"a" -> 1, "b" -> 2|>` on the link:
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// let map = BTreeMap::from([("a", 1), ("b", 2)]);
+    ///
+    /// link.put_association(
+    ///     &map,
+    ///     |link, key| link.put_str(key),
+    ///     |link, &value| link.put_i64(value),
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(
+    ///     link.get_expr().unwrap().to_string(),
+    ///     "System`Association[System`Rule[\"a\", 1], System`Rule[\"b\", 2]]"
+    /// );
+    /// ```
+    pub fn put_association<I, K, V, FK, FV>(
+        &mut self,
+        entries: I,
+        mut put_key: FK,
+        mut put_value: FV,
+    ) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        I::IntoIter: ExactSizeIterator,
+        FK: FnMut(&mut Link, K) -> Result<(), Error>,
+        FV: FnMut(&mut Link, V) -> Result<(), Error>,
+    {
+        let entries = entries.into_iter();
+
+        self.put_function("System`Association", entries.len())?;
+
+        for (key, value) in entries {
+            self.put_function("System`Rule", 2)?;
+            put_key(self, key)?;
+            put_value(self, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Put an [`Association`](https://reference.wolfram.com/language/ref/Association.html)
+    /// onto this link, using `build` to write each of its rules.
+    ///
+    /// Unlike [`Link::put_association()`], the number of entries does not need to be
+    /// known in advance. The rules added by `build` are buffered, and the association is
+    /// written once `build` returns. If `build` returns an error, nothing is written to
+    /// this link.
+    ///
+    /// # Example
+    ///
+    /// Put the expression `<|"a" -> 1, "b" -> {2, 3}|>` on the link:
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_association_with(|assoc| {
+    ///     assoc.rule("a", |link| link.put_i64(1))?;
+    ///     assoc.rule("b", |link| {
+    ///         link.put_function("System`List", 2)?;
+    ///         link.put_i64(2)?;
+    ///         link.put_i64(3)
+    ///     })?;
+    ///     Ok(())
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(
+    ///     link.get_expr().unwrap().to_string(),
+    ///     "System`Association[System`Rule[\"a\", 1], System`Rule[\"b\", System`List[2, 3]]]"
+    /// );
+    /// ```
+    pub fn put_association_with<F>(&mut self, build: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut AssociationBuilder) -> Result<(), Error>,
+    {
+        let mut builder = AssociationBuilder {
+            buffer: Link::new_loopback()?,
+            count: 0,
+        };
+
+        let () = build(&mut builder)?;
+
+        let AssociationBuilder { mut buffer, count } = builder;
+
+        self.put_function("System`Association", count)?;
+
+        if count > 0 {
+            buffer.transfer_to_end_of_loopback_link(self)?;
+        }
+
+        Ok(())
+    }
+
+    //==================================
+    // Null and Missing
+    //==================================
+
+    /// Put the symbol [`Null`](https://reference.wolfram.com/language/ref/Null.html)
+    /// onto this link.
+    ///
+    /// See also [`Link::put_missing()`] and [`Link::get_optional()`].
+    pub fn put_null(&mut self) -> Result<(), Error> {
+        self.put_symbol("System`Null")
+    }
+
+    /// Put a [`Missing[]`](https://reference.wolfram.com/language/ref/Missing.html)
+    /// expression onto this link.
+    ///
+    /// If `reason` is `Some`, the expression `Missing["reason"]` is written. Otherwise,
+    /// `Missing[]` is written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_missing(Some("NotFound")).unwrap();
+    ///
+    /// assert_eq!(link.get_expr().unwrap().to_string(), "System`Missing[\"NotFound\"]");
+    /// ```
+    pub fn put_missing(&mut self, reason: Option<&str>) -> Result<(), Error> {
+        match reason {
+            Some(reason) => {
+                self.put_function("System`Missing", 1)?;
+                self.put_str(reason)?;
+            },
+            None => self.put_function("System`Missing", 0)?,
+        }
+
+        Ok(())
+    }
+
+    //==================================
+    // Quantity
+    //==================================
+
+    /// Put a [`Quantity[value, "unit"]`](https://reference.wolfram.com/language/ref/Quantity.html)
+    /// expression onto this link.
+    ///
+    /// See also [`Link::get_quantity()`].
+    pub fn put_quantity(&mut self, value: f64, unit: &str) -> Result<(), Error> {
+        self.put_function("System`Quantity", 2)?;
+        self.put_f64(value)?;
+        self.put_str(unit)
+    }
+
     //==================================
     // Numerics
     //==================================
@@ -228,6 +524,15 @@ impl Link {
         Ok(())
     }
 
+    /// Put a signed 8-bit integer.
+    ///
+    /// WSTP's 8-bit integer functions are unsigned, so `value` is written using
+    /// [`WSPutInteger16()`](https://reference.wolfram.com/language/ref/c/WSPutInteger16.html).
+    /// This ensures that negative values are received as negative integers.
+    pub fn put_i8(&mut self, value: i8) -> Result<(), Error> {
+        self.put_i16(i16::from(value))
+    }
+
     /// *WSTP C API Documentation:* [`WSPutReal64()`](https://reference.wolfram.com/language/ref/c/WSPutReal64.html)
     pub fn put_f64(&mut self, value: f64) -> Result<(), Error> {
         if unsafe { WSPutReal64(self.raw_link, value) } == 0 {
@@ -289,6 +594,58 @@ impl Link {
         Ok(())
     }
 
+    /// Put a multidimensional array of [`i64`] whose elements are stored in `data` in
+    /// the specified `order`.
+    ///
+    /// WSTP transmits arrays in row-major order, so if `order` is
+    /// [`ArrayOrder::ColumnMajor`], the elements are reordered while they are copied.
+    /// The array read from the other end of the link has the same logical elements and
+    /// `dimensions` as the array in `data`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::{ArrayOrder, Link};
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// // The 2x3 matrix {{1, 2, 3}, {4, 5, 6}}, in column-major order.
+    /// let data = [1, 4, 2, 5, 3, 6];
+    ///
+    /// link.put_i64_array_with_order(&data, &[2, 3], ArrayOrder::ColumnMajor)
+    ///     .unwrap();
+    ///
+    /// let array = link.get_i64_array().unwrap();
+    ///
+    /// assert_eq!(array.data(), &[1, 2, 3, 4, 5, 6]);
+    /// assert_eq!(array.dimensions(), &[2, 3]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the product of `dimensions` is not equal to `data.len()`.
+    pub fn put_i64_array_with_order(
+        &mut self,
+        data: &[i64],
+        dimensions: &[usize],
+        order: ArrayOrder,
+    ) -> Result<(), Error> {
+        assert_eq!(
+            data.len(),
+            dimensions.iter().product(),
+            "data length does not equal product of dimensions"
+        );
+
+        match order {
+            ArrayOrder::RowMajor => self.put_i64_array(data, dimensions),
+            ArrayOrder::ColumnMajor => {
+                let data: Vec<i64> = get::from_column_major(data, dimensions);
+
+                self.put_i64_array(&data, dimensions)
+            },
+        }
+    }
+
     /// Put a multidimensional array of [`i32`].
     ///
     /// # Panics
@@ -471,6 +828,264 @@ impl Link {
 
         Ok(())
     }
+
+    //==================================
+    // Complex numeric arrays
+    //==================================
+
+    /// Put a multidimensional array of complex numbers, where each element of `data` is
+    /// a `(real, imaginary)` pair.
+    ///
+    /// The array is written as nested `List`s of
+    /// [`Complex[re, im]`](https://reference.wolfram.com/language/ref/Complex.html)
+    /// values, which the Kernel will pack into a complex packed array if requested.
+    ///
+    /// See also [`Link::get_complex_f64_array()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_complex_f64_array(&[(1.0, 2.0), (3.0, -4.0)], &[2]).unwrap();
+    ///
+    /// assert_eq!(
+    ///     link.get_expr().unwrap().to_string(),
+    ///     "System`List[System`Complex[1., 2.], System`Complex[3., -4.]]"
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the product of `dimensions` is not equal to `data.len()`.
+    pub fn put_complex_f64_array(
+        &mut self,
+        data: &[(f64, f64)],
+        dimensions: &[usize],
+    ) -> Result<(), Error> {
+        assert_eq!(
+            data.len(),
+            dimensions.iter().product(),
+            "data length does not equal product of dimensions"
+        );
+
+        self.put_complex_f64_array_level(data, dimensions)
+    }
+
+    fn put_complex_f64_array_level(
+        &mut self,
+        data: &[(f64, f64)],
+        dimensions: &[usize],
+    ) -> Result<(), Error> {
+        let (length, rest) = match dimensions.split_first() {
+            Some(split) => split,
+            None => {
+                let (re, im) = data[0];
+
+                self.put_function("System`Complex", 2)?;
+                self.put_f64(re)?;
+                self.put_f64(im)?;
+
+                return Ok(());
+            },
+        };
+
+        self.put_function("System`List", *length)?;
+
+        let row_length: usize = rest.iter().product();
+
+        for row in 0..*length {
+            let row_data = &data[row * row_length..(row + 1) * row_length];
+
+            self.put_complex_f64_array_level(row_data, rest)?;
+        }
+
+        Ok(())
+    }
+
+    //==================================
+    // String arrays
+    //==================================
+
+    /// Put a multidimensional array of strings.
+    ///
+    /// Strings cannot be stored in a packed array, so the array is written as nested
+    /// `List`s of strings.
+    ///
+    /// An error is returned, and nothing is written, if the product of `dimensions` is
+    /// not equal to `data.len()`.
+    ///
+    /// See also [`Link::get_string_array()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_string_array(&["a", "b", "c", "d", "e", "f"], &[3, 2]).unwrap();
+    ///
+    /// assert_eq!(
+    ///     link.get_expr().unwrap().to_string(),
+    ///     "System`List[System`List[\"a\", \"b\"], System`List[\"c\", \"d\"], \
+    ///      System`List[\"e\", \"f\"]]"
+    /// );
+    /// ```
+    pub fn put_string_array(
+        &mut self,
+        data: &[&str],
+        dimensions: &[usize],
+    ) -> Result<(), Error> {
+        let len: usize = dimensions.iter().product();
+
+        if data.len() != len {
+            return Err(Error::custom(format!(
+                "put_string_array: data length {} does not equal product of dimensions \
+                 {:?}",
+                data.len(),
+                dimensions
+            )));
+        }
+
+        self.put_string_array_level(data, dimensions)
+    }
+
+    fn put_string_array_level(
+        &mut self,
+        data: &[&str],
+        dimensions: &[usize],
+    ) -> Result<(), Error> {
+        let (length, rest) = match dimensions.split_first() {
+            Some(split) => split,
+            None => return self.put_str(data[0]),
+        };
+
+        self.put_function("System`List", *length)?;
+
+        let row_length: usize = rest.iter().product();
+
+        for row in 0..*length {
+            let row_data = &data[row * row_length..(row + 1) * row_length];
+
+            self.put_string_array_level(row_data, rest)?;
+        }
+
+        Ok(())
+    }
+
+    //==================================
+    // Sparse arrays
+    //==================================
+
+    /// Put a two-dimensional real
+    /// [`SparseArray`](https://reference.wolfram.com/language/ref/SparseArray.html)
+    /// with dimensions `(rows, columns)`, whose nonzero elements are given by `rules`.
+    ///
+    /// Each element of `rules` is a `((row, column), value)` pair, using zero-based
+    /// indices. The array is written in the rule-based form
+    /// `SparseArray[{{i, j} -> value, ...}, {rows, columns}]`, using the one-based
+    /// indices expected by the Wolfram Language. Elements that are not given by `rules`
+    /// are zero.
+    ///
+    /// Arrays of rank other than two are not supported.
+    ///
+    /// See also [`Link::get_sparse_array()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// link.put_sparse_array(&[((0, 0), 1.5), ((999, 999), 2.5)], (1000, 1000))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     link.get_sparse_array(),
+    ///     Ok((vec![(0, 0), (999, 999)], vec![1.5, 2.5], (1000, 1000)))
+    /// );
+    /// ```
+    pub fn put_sparse_array(
+        &mut self,
+        rules: &[((usize, usize), f64)],
+        dims: (usize, usize),
+    ) -> Result<(), Error> {
+        let (rows, columns) = dims;
+
+        // Validate the indices before anything is written to this link.
+        for &((row, column), _) in rules {
+            if row >= rows || column >= columns {
+                return Err(Error::custom(format!(
+                    "put_sparse_array: index ({}, {}) is out of bounds for dimensions \
+                     ({}, {})",
+                    row, column, rows, columns
+                )));
+            }
+        }
+
+        let to_i64 = |index: usize| -> Result<i64, Error> {
+            i64::try_from(index).map_err(|err| {
+                Error::custom(format!("put_sparse_array: index overflows i64: {}", err))
+            })
+        };
+
+        self.put_function("System`SparseArray", 2)?;
+        self.put_function("System`List", rules.len())?;
+
+        for &((row, column), value) in rules {
+            self.put_function("System`Rule", 2)?;
+            self.put_i64_array(&[to_i64(row)? + 1, to_i64(column)? + 1], &[2])?;
+            self.put_f64(value)?;
+        }
+
+        self.put_i64_array(&[to_i64(rows)?, to_i64(columns)?], &[2])
+    }
+}
+
+/// Builder used to write the rules of an association incrementally.
+///
+/// This type is used by [`Link::put_association_with()`].
+pub struct AssociationBuilder {
+    /// Loopback link that rules are written to until the association is complete.
+    buffer: Link,
+    count: usize,
+}
+
+impl AssociationBuilder {
+    /// Add the rule `key -> value` to the association, where `put_value` is used to
+    /// write the value.
+    pub fn rule<F>(&mut self, key: &str, put_value: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Link) -> Result<(), Error>,
+    {
+        self.rule_with(|link| link.put_str(key), put_value)
+    }
+
+    /// Add the rule `key -> value` to the association, where `put_key` and `put_value`
+    /// are used to write the key and the value.
+    ///
+    /// If an error is returned, the rule may have been partially written, and the
+    /// error should be returned from the closure passed to
+    /// [`Link::put_association_with()`].
+    pub fn rule_with<FK, FV>(&mut self, put_key: FK, put_value: FV) -> Result<(), Error>
+    where
+        FK: FnOnce(&mut Link) -> Result<(), Error>,
+        FV: FnOnce(&mut Link) -> Result<(), Error>,
+    {
+        let AssociationBuilder { buffer, count } = self;
+
+        buffer.put_function("System`Rule", 2)?;
+        put_key(buffer)?;
+        put_value(buffer)?;
+
+        *count += 1;
+
+        Ok(())
+    }
 }
 
 /// Convert `dimensions` to a `Vec<i32>`, which can further be converted to a
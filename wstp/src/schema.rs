@@ -0,0 +1,193 @@
+//! Debug-only checking that data is read from a link in the order it was written.
+//!
+//! A common class of bug in code that exchanges data over WSTP is writing values in one
+//! order and reading them in another. WSTP typically reports these mistakes as a
+//! sequence error (`WSEGSEQ`) at some later point, far from the actual cause.
+//!
+//! A [`Schema`] records the sequence of tokens written by a function, which can then be
+//! compared against the tokens available to read on the receiving side using
+//! [`Link::expect_schema()`].
+
+use std::fmt;
+
+use crate::{Error, Link, Token};
+
+/// The sequence of tokens written to a link by a function.
+///
+/// Use [`Link::record_schema()`] to create a `Schema`, and [`Link::expect_schema()`] to
+/// check that the data available to read from a link matches it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schema {
+    tokens: Vec<SchemaToken>,
+}
+
+/// The shape of a single [`Token`].
+///
+/// The values of numbers and strings are not recorded, because they typically differ
+/// between messages that follow the same schema. Symbols are recorded by name, because
+/// they are used as function heads and enumeration values.
+#[derive(Debug, Clone, PartialEq)]
+enum SchemaToken {
+    Integer,
+    Real,
+    String,
+    Symbol(String),
+    Function { length: usize },
+}
+
+impl Schema {
+    /// Get the number of tokens in this schema.
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Returns `true` if this schema contains no tokens.
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
+
+impl Link {
+    /// Record the sequence of tokens written by `put`.
+    ///
+    /// `put` is called with a new loopback link, and the tokens it writes are recorded
+    /// in the returned [`Schema`]. The values of numbers and strings are not recorded,
+    /// only their types.
+    ///
+    /// Use [`Link::expect_schema()`] to check that the data available to read from a
+    /// link was written in the same order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstp::Link;
+    ///
+    /// fn put_point(link: &mut Link, x: i64, y: f64) -> Result<(), wstp::Error> {
+    ///     link.put_function("Global`Point", 2)?;
+    ///     link.put_i64(x)?;
+    ///     link.put_f64(y)
+    /// }
+    ///
+    /// let schema = Link::record_schema(|link| put_point(link, 0, 0.0)).unwrap();
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// put_point(&mut link, 1, 2.5).unwrap();
+    ///
+    /// link.expect_schema(&schema).unwrap();
+    ///
+    /// assert_eq!(link.test_head("Global`Point"), Ok(2));
+    /// assert_eq!(link.get_i64(), Ok(1));
+    /// assert_eq!(link.get_f64(), Ok(2.5));
+    /// ```
+    pub fn record_schema<F>(put: F) -> Result<Schema, Error>
+    where
+        F: FnOnce(&mut Link) -> Result<(), Error>,
+    {
+        let mut loopback = Link::new_loopback()?;
+
+        let () = put(&mut loopback)?;
+
+        let mut tokens = Vec::new();
+
+        while loopback.is_ready() {
+            tokens.push(SchemaToken::from_token(loopback.get_token()?));
+        }
+
+        Ok(Schema { tokens })
+    }
+
+    /// Check that the next tokens available to read from this link match `schema`,
+    /// without consuming them.
+    ///
+    /// This check is only performed in builds with `debug_assertions` enabled. In
+    /// release builds, this function does nothing and returns `Ok(())`.
+    ///
+    /// This function will block until at least as many tokens as are in `schema` are
+    /// available to read, or an error occurs.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the tokens available to read from this link do not
+    /// match `schema`, with a message describing the first mismatched token.
+    pub fn expect_schema(&mut self, schema: &Schema) -> Result<(), Error> {
+        if !cfg!(debug_assertions) {
+            return Ok(());
+        }
+
+        let found: Vec<Result<SchemaToken, Error>> = self.peek(|link: &mut Link| {
+            let mut found = Vec::with_capacity(schema.len());
+
+            for _ in 0..schema.len() {
+                match link.get_token() {
+                    Ok(token) => found.push(Ok(SchemaToken::from_token(token))),
+                    Err(err) => {
+                        found.push(Err(err));
+                        break;
+                    },
+                }
+            }
+
+            found
+        })?;
+
+        let mismatch = schema
+            .tokens
+            .iter()
+            .zip(&found)
+            .position(|(expected, found)| found.as_ref() != Ok(expected));
+
+        if let Some(index) = mismatch {
+            let found: Vec<String> = found
+                .iter()
+                .map(|token| match token {
+                    Ok(token) => token.to_string(),
+                    Err(err) => format!("<error: {}>", err),
+                })
+                .collect();
+
+            panic!(
+                "expect_schema(): link data does not match schema at token {}:\n\
+                 \x20 expected: {}\n\
+                 \x20    found: {}\n\n\
+                 expected tokens: [{}]\n\
+                 \x20  found tokens: [{}]",
+                index,
+                schema.tokens[index],
+                found[index],
+                join(schema.tokens.iter().map(ToString::to_string)),
+                join(found.iter().cloned()),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl SchemaToken {
+    fn from_token(token: Token) -> Self {
+        match token {
+            Token::Integer(_) => SchemaToken::Integer,
+            Token::Real(_) => SchemaToken::Real,
+            Token::String(_) => SchemaToken::String,
+            Token::Symbol(symbol) => SchemaToken::Symbol(symbol.as_str().to_owned()),
+            Token::Function { length } => SchemaToken::Function { length },
+        }
+    }
+}
+
+impl fmt::Display for SchemaToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaToken::Integer => write!(f, "Integer"),
+            SchemaToken::Real => write!(f, "Real"),
+            SchemaToken::String => write!(f, "String"),
+            SchemaToken::Symbol(name) => write!(f, "Symbol({})", name),
+            SchemaToken::Function { length } => write!(f, "Function[{}]", length),
+        }
+    }
+}
+
+fn join<I: Iterator<Item = String>>(iter: I) -> String {
+    iter.collect::<Vec<String>>().join(", ")
+}
@@ -1,13 +1,125 @@
+use wolfram_expr::Expr;
+
 use crate::{
     sys::{self, WSLINK},
     Error, Link,
 };
 
 use std::{
+    cell::Cell,
     collections::HashMap,
+    convert::TryFrom,
+    ops::ControlFlow,
+    os::raw::{c_int, c_ulong},
     sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
 };
 
+//======================================
+// Timeout
+//======================================
+
+/// Maximum duration to wait for an operation to complete, and the interval at which
+/// to poll for completion while waiting.
+///
+/// Operations that accept a `Timeout` are implemented by periodically checking whether
+/// the operation has completed. The poll interval controls the tradeoff between latency
+/// and CPU usage: a shorter interval detects completion sooner, at the cost of waking up
+/// more often. The default poll interval is [`Timeout::DEFAULT_POLL_INTERVAL`].
+///
+/// A [`Duration`] can be used anywhere a `Timeout` is expected, in which case the
+/// default poll interval is used.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use wstp::{Link, Protocol, Timeout};
+///
+/// let mut listener = Link::listen(Protocol::IntraProcess, "").unwrap();
+///
+/// // Wait using the default poll interval.
+/// listener.wait_timeout(Duration::from_millis(50)).unwrap();
+///
+/// // Wait, checking for data every millisecond.
+/// let timeout = Timeout::new(Duration::from_millis(50))
+///     .with_poll_interval(Duration::from_millis(1));
+///
+/// listener.wait_timeout(timeout).unwrap();
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Timeout {
+    /// Maximum duration to wait.
+    pub duration: Duration,
+    /// Interval between checks for completion of the operation being waited on.
+    pub poll_interval: Duration,
+}
+
+impl Timeout {
+    /// Default poll interval used by [`Timeout::new()`].
+    pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    /// Construct a new `Timeout` with the default poll interval.
+    pub const fn new(duration: Duration) -> Self {
+        Timeout {
+            duration,
+            poll_interval: Timeout::DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Set the interval between checks for completion of the operation being waited on.
+    pub const fn with_poll_interval(self, poll_interval: Duration) -> Self {
+        let Timeout {
+            duration,
+            poll_interval: _,
+        } = self;
+
+        Timeout {
+            duration,
+            poll_interval,
+        }
+    }
+}
+
+impl Timeout {
+    /// Repeatedly call `func` every `poll_interval` until it returns `Some`, or until
+    /// `duration` has elapsed.
+    ///
+    /// `func` is always called at least once, and is called one final time once the
+    /// timeout has elapsed.
+    pub(crate) fn poll<T, F>(&self, mut func: F) -> Result<Option<T>, Error>
+    where
+        F: FnMut() -> Result<Option<T>, Error>,
+    {
+        let Timeout {
+            duration,
+            poll_interval,
+        } = *self;
+
+        let start = Instant::now();
+
+        loop {
+            if let Some(value) = func()? {
+                return Ok(Some(value));
+            }
+
+            let elapsed = start.elapsed();
+
+            if elapsed >= duration {
+                return Ok(None);
+            }
+
+            std::thread::sleep(poll_interval.min(duration - elapsed));
+        }
+    }
+}
+
+impl From<Duration> for Timeout {
+    fn from(duration: Duration) -> Self {
+        Timeout::new(duration)
+    }
+}
+
 //======================================
 // Wait Callbacks Global
 //======================================
@@ -37,6 +149,121 @@ fn get_wait_callbacks_lock() -> std::sync::MutexGuard<'static, WaitCallbacksHash
     lock
 }
 
+//======================================
+// Activation Timeout Global
+//======================================
+
+thread_local! {
+    /// Deadline and poll interval used by [`activate_timeout_yield_function()`].
+    ///
+    /// [`WSActivate()`][sys::WSActivate] calls the link's yield function on the thread
+    /// that is performing the activation, so a thread-local value is sufficient to
+    /// pass the deadline from [`Link::activate_timeout()`] to the yield function.
+    static ACTIVATE_DEADLINE: Cell<Option<(Instant, Duration)>> = const { Cell::new(None) };
+}
+
+/// Yield function used by [`Link::activate_timeout()`].
+///
+/// Returning a non-zero value from a yield function aborts the blocking WSTP operation
+/// that called it.
+unsafe extern "C" fn activate_timeout_yield_function(
+    _: WSLINK,
+    _: sys::WSYieldParameters,
+) -> std::os::raw::c_int {
+    let Some((deadline, poll_interval)) = ACTIVATE_DEADLINE.with(Cell::get) else {
+        return 0;
+    };
+
+    let now = Instant::now();
+
+    if now >= deadline {
+        return 1;
+    }
+
+    std::thread::sleep(poll_interval.min(deadline - now));
+
+    0
+}
+
+//======================================
+// Ready parallel
+//======================================
+
+/// Wait until at least one of `links` has data available to be read, giving up after
+/// `timeout` has elapsed.
+///
+/// Returns the indices into `links` of every link that has data available. An empty
+/// `Vec` is returned if the timeout elapsed before any link became ready, or if
+/// `links` is empty.
+///
+/// Waiting is performed by WSTP itself, without polling, so this is more efficient
+/// than repeatedly calling [`Link::is_ready()`] on a large number of links.
+///
+/// There is no polling fallback for when `WSReadyParallel()` is unavailable: it is
+/// declared by the bindings for every supported WSTP version, and the WSTP library is
+/// linked statically, so a missing symbol is a link error when building, not a
+/// runtime failure.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use wstp::Link;
+///
+/// let mut a = Link::new_loopback().unwrap();
+/// let mut b = Link::new_loopback().unwrap();
+///
+/// b.put_i64(1).unwrap();
+///
+/// let ready = wstp::ready_parallel(&mut [&mut a, &mut b], Duration::from_millis(10));
+///
+/// assert_eq!(ready, Ok(vec![1]));
+/// ```
+///
+/// *WSTP C API Documentation:* [`WSReadyParallel()`](https://reference.wolfram.com/language/ref/c/WSReadyParallel.html)
+pub fn ready_parallel(
+    links: &mut [&mut Link],
+    timeout: Duration,
+) -> Result<Vec<usize>, Error> {
+    if links.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut raw_links: Vec<WSLINK> = links.iter().map(|link| link.raw_link).collect();
+
+    let count = c_int::try_from(raw_links.len()).map_err(|_| {
+        Error::custom(format!("too many links to wait on: {}", raw_links.len()))
+    })?;
+
+    let timeval = sys::wstimeval {
+        tv_sec: c_ulong::try_from(timeout.as_secs()).unwrap_or(c_ulong::MAX),
+        tv_usec: c_ulong::from(timeout.subsec_micros()),
+    };
+
+    let result: c_int = crate::with_raw_stdenv(|raw_stdenv| unsafe {
+        sys::WSReadyParallel(raw_stdenv, raw_links.as_mut_ptr(), count, timeval)
+    })?;
+
+    match result {
+        sys::WSREADYPARALLELTIMEDOUT => Ok(Vec::new()),
+        sys::WSREADYPARALLELERROR | sys::WSREADYPARALLELINVALIDARGUMENT => Err(
+            Error::custom(format!("WSReadyParallel returned error value: {}", result)),
+        ),
+        // `result` is the index of the first ready link. Check every link, since more
+        // than one may be ready.
+        _ if result >= 0 => Ok(links
+            .iter()
+            .enumerate()
+            .filter(|(_, link)| link.is_ready())
+            .map(|(index, _)| index)
+            .collect()),
+        _ => Err(Error::custom(format!(
+            "WSReadyParallel returned unexpected value: {}",
+            result
+        ))),
+    }
+}
+
 //======================================
 // Link Implementation
 //======================================
@@ -58,6 +285,209 @@ impl Link {
         }
     }
 
+    /// Wait for data to become available, giving up after the duration specified by
+    /// `timeout` has elapsed.
+    ///
+    /// `true` will be returned if data is available. `false` will be returned if the
+    /// timeout elapsed first.
+    ///
+    /// See [`Timeout`] for a description of the poll interval used while waiting.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use wstp::{Link, Protocol};
+    ///
+    /// let mut listener = Link::listen(Protocol::IntraProcess, "").unwrap();
+    ///
+    /// assert_eq!(listener.wait_timeout(Duration::from_millis(20)), Ok(false));
+    /// ```
+    pub fn wait_timeout<T: Into<Timeout>>(&mut self, timeout: T) -> Result<bool, Error> {
+        let Timeout {
+            duration,
+            poll_interval,
+        } = timeout.into();
+
+        let start = Instant::now();
+
+        self.wait_with_callback(move |_: &mut Link| {
+            let elapsed = start.elapsed();
+
+            if elapsed >= duration {
+                return ControlFlow::Break(());
+            }
+
+            std::thread::sleep(poll_interval.min(duration - elapsed));
+
+            ControlFlow::Continue(())
+        })
+    }
+
+    /// Wait for data to become available to read, giving up after the duration
+    /// specified by `timeout` has elapsed.
+    ///
+    /// `true` will be returned if data is available. `false` will be returned if the
+    /// timeout elapsed first.
+    ///
+    /// Unlike [`Link::wait_timeout()`], this method is implemented by polling
+    /// [`Link::is_ready()`], and so behaves uniformly for links of every [`Protocol`][crate::Protocol],
+    /// including loopback links.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use wstp::Link;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// assert_eq!(link.wait_ready(Duration::from_millis(10)), Ok(false));
+    ///
+    /// link.put_i64(5).unwrap();
+    ///
+    /// assert_eq!(link.wait_ready(Duration::from_millis(10)), Ok(true));
+    /// ```
+    pub fn wait_ready<T: Into<Timeout>>(&mut self, timeout: T) -> Result<bool, Error> {
+        let ready = timeout
+            .into()
+            .poll(|| Ok(if self.is_ready() { Some(()) } else { None }))?;
+
+        Ok(ready.is_some())
+    }
+
+    /// Read an expression from this link, giving up if no data becomes available
+    /// before the duration specified by `timeout` has elapsed.
+    ///
+    /// `Ok(None)` is returned if the timeout elapsed before any data was available to
+    /// read. In that case nothing has been read, and the link can be used normally.
+    ///
+    /// This method waits using [`Link::wait_ready()`], and then reads the expression
+    /// using [`Link::get_expr()`].
+    ///
+    /// # Partially received expressions
+    ///
+    /// WSTP can report whether *any* data is available to be read, but not whether an
+    /// entire expression has arrived. The timeout therefore only applies to waiting for
+    /// the start of an expression: once data is available, this method will block until
+    /// the rest of the expression has been received. This ensures the link is never left
+    /// positioned in the middle of an expression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use wstp::Link;
+    /// use wolfram_expr::Expr;
+    ///
+    /// let mut link = Link::new_loopback().unwrap();
+    ///
+    /// assert_eq!(link.get_expr_timeout(Duration::from_millis(10)), Ok(None));
+    ///
+    /// link.put_i64(5).unwrap();
+    ///
+    /// assert_eq!(
+    ///     link.get_expr_timeout(Duration::from_millis(10)),
+    ///     Ok(Some(Expr::from(5)))
+    /// );
+    /// ```
+    pub fn get_expr_timeout<T: Into<Timeout>>(
+        &mut self,
+        timeout: T,
+    ) -> Result<Option<Expr>, Error> {
+        if !self.wait_ready(timeout)? {
+            return Ok(None);
+        }
+
+        self.get_expr().map(Some)
+    }
+
+    /// Activate this link, giving up after the duration specified by `timeout` has
+    /// elapsed.
+    ///
+    /// This is equivalent to [`Link::activate()`], except that an error is returned if
+    /// the link is not activated before the timeout elapses. For that error,
+    /// [`Error::is_timeout()`] returns `true`. A link whose activation timed out should
+    /// be closed.
+    ///
+    /// While activating, a yield function is temporarily installed on this link, which
+    /// is called periodically by WSTP and aborts the activation once the deadline has
+    /// passed. Any previously installed yield function is restored afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use wstp::{Link, Protocol};
+    ///
+    /// // Nothing ever connects to this link.
+    /// let mut listener = Link::listen(Protocol::IntraProcess, "").unwrap();
+    ///
+    /// assert!(listener.activate_timeout(Duration::from_millis(20)).is_err());
+    /// ```
+    ///
+    /// *WSTP C API Documentation:* [`WSSetYieldFunction()`](https://reference.wolfram.com/language/ref/c/WSSetYieldFunction.html)
+    pub fn activate_timeout<T: Into<Timeout>>(
+        &mut self,
+        timeout: T,
+    ) -> Result<(), Error> {
+        let Link { raw_link } = *self;
+
+        let Timeout {
+            duration,
+            poll_interval,
+        } = timeout.into();
+
+        let yield_function: sys::WSYieldFunctionObject =
+            crate::env::with_raw_stdenv(|raw_env| unsafe {
+                sys::WSCreateYieldFunction(
+                    raw_env,
+                    Some(activate_timeout_yield_function),
+                    std::ptr::null_mut(),
+                )
+            })?;
+
+        if yield_function.is_none() {
+            return Err(Error::custom(
+                "activate_timeout: unable to create yield function".to_owned(),
+            ));
+        }
+
+        let previous = unsafe { sys::WSYieldFunction(raw_link) };
+
+        if unsafe { sys::WSSetYieldFunction(raw_link, yield_function) } == 0 {
+            unsafe { sys::WSDestroyYieldFunction(yield_function) };
+            return Err(self.error_or_unknown());
+        }
+
+        let deadline = Instant::now() + duration;
+
+        ACTIVATE_DEADLINE.with(|cell| cell.set(Some((deadline, poll_interval))));
+
+        let activated = unsafe { sys::WSActivate(raw_link) } != 0;
+
+        let timed_out = Instant::now() >= deadline;
+
+        ACTIVATE_DEADLINE.with(|cell| cell.set(None));
+
+        unsafe {
+            sys::WSSetYieldFunction(raw_link, previous);
+            sys::WSDestroyYieldFunction(yield_function);
+        }
+
+        match (activated, timed_out) {
+            (true, _) => {
+                crate::link_state::update(raw_link, |state| state.unactivated = false);
+                Ok(())
+            },
+            (false, true) => Err(Error::timeout(format!(
+                "link activation timed out after {:?}",
+                duration
+            ))),
+            (false, false) => Err(self.error_or_unknown()),
+        }
+    }
+
     /// Wait for data to become available, periodically calling a callback.
     ///
     /// `true` will be returned if data is available. `false` will be returned if the
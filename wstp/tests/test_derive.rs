@@ -0,0 +1,152 @@
+#![cfg(feature = "derive")]
+
+use std::fmt::Debug;
+
+use wolfram_expr::{Expr, Symbol};
+use wstp::{FromLink, Link, ToLink};
+
+#[derive(ToLink, FromLink, Debug, PartialEq)]
+enum Shape {
+    Circle(f64),
+    Rect(f64, f64),
+    Labeled { label: String, shape: Box<Shape> },
+    Empty,
+}
+
+#[derive(ToLink, FromLink, Debug, PartialEq)]
+struct Point {
+    x: i64,
+    y: i64,
+    name: Option<String>,
+}
+
+#[derive(ToLink, FromLink, Debug, PartialEq)]
+#[wstp(context = "MyPackage`")]
+struct Wrapper(Vec<Point>);
+
+fn check_roundtrip<T: ToLink + FromLink + Debug + PartialEq>(value: T) {
+    let mut link = Link::new_loopback().unwrap();
+
+    value.to_link(&mut link).unwrap();
+
+    assert_eq!(T::from_link(&mut link), Ok(value));
+}
+
+#[test]
+fn test_derive_roundtrip() {
+    check_roundtrip(Shape::Circle(1.5));
+    check_roundtrip(Shape::Rect(1.0, 2.0));
+    check_roundtrip(Shape::Empty);
+    check_roundtrip(Shape::Labeled {
+        label: String::from("unit"),
+        shape: Box::new(Shape::Circle(1.0)),
+    });
+
+    check_roundtrip(Point {
+        x: 1,
+        y: -2,
+        name: None,
+    });
+
+    check_roundtrip(Wrapper(vec![Point {
+        x: 3,
+        y: 4,
+        name: Some(String::from("p")),
+    }]));
+}
+
+#[test]
+fn test_derive_expression_form() {
+    let mut link = Link::new_loopback().unwrap();
+
+    Shape::Rect(1.0, 2.0).to_link(&mut link).unwrap();
+    Shape::Empty.to_link(&mut link).unwrap();
+
+    assert_eq!(
+        link.get_expr(),
+        Ok(Expr::normal(
+            Symbol::new("Global`Rect"),
+            vec![Expr::real(1.0), Expr::real(2.0)]
+        ))
+    );
+    assert_eq!(link.get_expr(), Ok(Expr::symbol(Symbol::new("Global`Empty"))));
+
+    Wrapper(vec![]).to_link(&mut link).unwrap();
+
+    assert_eq!(
+        link.get_expr(),
+        Ok(Expr::normal(
+            Symbol::new("MyPackage`Wrapper"),
+            vec![Expr::normal(Symbol::new("System`List"), vec![])]
+        ))
+    );
+}
+
+#[test]
+fn test_derive_errors() {
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_function("Global`Triangle", 1).unwrap();
+    link.put_f64(1.0).unwrap();
+
+    assert_eq!(
+        Shape::from_link(&mut link).unwrap_err().to_string(),
+        "WSTP error: Shape: unknown variant: Triangle"
+    );
+
+    link.put_function("System`Association", 1).unwrap();
+    link.put_function("System`Rule", 2).unwrap();
+    link.put_str("x").unwrap();
+    link.put_i64(1).unwrap();
+
+    assert_eq!(
+        Point::from_link(&mut link).unwrap_err().to_string(),
+        "WSTP error: Point: missing field: y"
+    );
+}
+
+#[test]
+fn test_derive_from_kernel() {
+    let mut link = Link::new_loopback().unwrap();
+
+    // Expressions sent by the Kernel use System` symbols without a context.
+    link.put_function("MyPackage`Wrapper", 1).unwrap();
+    link.put_function("List", 1).unwrap();
+    link.put_function("Association", 3).unwrap();
+    for (key, value) in [("x", 1), ("y", 2)] {
+        link.put_function("Rule", 2).unwrap();
+        link.put_str(key).unwrap();
+        link.put_i64(value).unwrap();
+    }
+    link.put_function("Rule", 2).unwrap();
+    link.put_str("name").unwrap();
+    link.put_symbol("Null").unwrap();
+
+    assert_eq!(
+        Wrapper::from_link(&mut link),
+        Ok(Wrapper(vec![Point {
+            x: 1,
+            y: 2,
+            name: None,
+        }]))
+    );
+}
+
+#[allow(non_camel_case_types)]
+#[derive(ToLink, FromLink, Debug, PartialEq)]
+enum Keyword {
+    r#type,
+    r#struct(i64),
+}
+
+#[test]
+fn test_derive_raw_identifiers() {
+    check_roundtrip(Keyword::r#type);
+    check_roundtrip(Keyword::r#struct(1));
+
+    let mut link = Link::new_loopback().unwrap();
+
+    Keyword::r#type.to_link(&mut link).unwrap();
+
+    assert_eq!(link.get_symbol(), Ok(Symbol::new("Global`type")));
+}
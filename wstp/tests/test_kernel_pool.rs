@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use wolfram_app_discovery::WolframApp;
+use wolfram_expr::{Expr, Symbol};
+use wstp::kernel::KernelPool;
+
+fn kernel_executable_path() -> PathBuf {
+    let app = WolframApp::try_default()
+        .expect("unable to find any Wolfram Language installations");
+
+    app.kernel_executable_path().unwrap()
+}
+
+#[test]
+fn test_kernel_pool_size_zero() {
+    // The size is checked before any kernels are launched.
+    let path = PathBuf::from("/nonexistent/WolframKernel");
+
+    assert!(KernelPool::new(&path, 0).is_err());
+}
+
+#[test]
+#[ignore = "requires a Wolfram Language installation"]
+fn test_kernel_pool_reuses_healthy_kernel() {
+    let pool = KernelPool::new(&kernel_executable_path(), 1).unwrap();
+
+    let process_id = Expr::symbol(Symbol::new("System`$ProcessID"));
+
+    let pid = {
+        let mut kernel = pool.acquire().unwrap();
+        let link = kernel.link();
+
+        link.put_eval_packet(&process_id).unwrap();
+        link.flush().unwrap();
+        let pid = link.get_return_value().unwrap();
+
+        // Leave the result of an evaluation unread when the kernel is returned.
+        link.put_eval_packet(&Expr::from(5)).unwrap();
+        link.flush().unwrap();
+        link.wait_ready(std::time::Duration::from_secs(10)).unwrap();
+
+        pid
+    };
+
+    let mut kernel = pool.acquire().unwrap();
+    let link = kernel.link();
+
+    // The kernel passed the health check and was not relaunched.
+    link.put_eval_packet(&process_id).unwrap();
+    link.flush().unwrap();
+    assert_eq!(link.get_return_value().unwrap(), pid);
+}
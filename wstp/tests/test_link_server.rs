@@ -177,6 +177,41 @@ fn test_link_server_using_callback() {
     // server.try_interface().unwrap();
 }
 
+/// Test that a panic in the callback passed to `new_with_callback()` does not stop the
+/// link server from accepting further connections.
+#[test]
+fn test_link_server_callback_panic() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let _guard = MUTEX.lock().unwrap();
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let server = LinkServer::new_with_callback(PORT, |mut link| {
+        if COUNTER.fetch_add(1, Ordering::SeqCst) == 0 {
+            panic!("panic in LinkServer callback");
+        }
+
+        link.put_i64(1).unwrap();
+        link.flush().unwrap();
+    })
+    .unwrap();
+
+    // Give the link server time to start listening for connections.
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    // The first connection causes the callback to panic.
+    let _ = Link::connect_to_link_server(("127.0.0.1", PORT));
+
+    // The server should still accept and handle the second connection.
+    let mut link = Link::connect_to_link_server(("127.0.0.1", PORT)).unwrap();
+
+    assert_eq!(link.get_i64(), Ok(1));
+    assert_eq!(COUNTER.load(Ordering::SeqCst), 2);
+
+    drop(server);
+}
+
 #[test]
 fn test_name_taken_error() {
     let _guard = MUTEX.lock().unwrap();
@@ -187,3 +222,21 @@ fn test_name_taken_error() {
 
     assert_eq!(b.code(), Some(sys::MLENAMETAKEN));
 }
+
+/// Test that the error returned by `LinkServer::bind()` describes each address that was
+/// tried.
+#[test]
+fn test_bind_error_lists_addresses() {
+    let _guard = MUTEX.lock().unwrap();
+
+    let _a = LinkServer::bind(("127.0.0.1", PORT)).unwrap();
+    let b = LinkServer::bind(("127.0.0.1", PORT))
+        .expect_err("multiple link servers bound to same port??");
+
+    assert_eq!(b.code(), Some(sys::MLENAMETAKEN));
+    assert!(
+        b.to_string().contains(&format!("tried 127.0.0.1:{} (", PORT)),
+        "{}",
+        b
+    );
+}
@@ -1,6 +1,6 @@
 use std::sync::Mutex;
 
-use wstp::{sys, Link, Protocol, UrgentMessage};
+use wstp::{sys, Link, Protocol, TokenType, UrgentMessage};
 
 /// Guard used to ensure the tests which bind to a port are run sequentially, so that
 /// port is free for each test.
@@ -32,7 +32,7 @@ fn check_send_data_across_link(mut link_a: Link, mut link_b: Link) {
         assert_eq!(got, 3.1415);
 
         {
-            link_a.put_raw_type(i32::from(sys::WSTKFUNC)).unwrap();
+            link_a.put_next_type(TokenType::Function).unwrap();
             link_a.put_arg_count(2).unwrap();
             link_a.put_symbol("Sin").unwrap();
             link_a.put_f64(1.0).unwrap();
@@ -88,14 +88,114 @@ fn test_intra_process_links() {
     check_send_data_across_link(listener, connector);
 }
 
-/// FIXME: IntraProcess-mode links ignore the `-linkname` device parameter and instead
-///        generate their own random string to use as a name. So we have to create the
-///        listener device first and then ask for it's name.
+/// IntraProcess-mode links ignore the `-linkname` device parameter and instead generate
+/// their own random string to use as a name, so `Link::listen()` rejects non-empty
+/// names instead of silently ignoring them.
 #[test]
-fn test_bug_intra_process_device_ignored_linkname() {
+fn test_intra_process_listen_rejects_linkname() {
     let name: String = random_link_name();
-    let listener = Link::listen(Protocol::IntraProcess, &name).unwrap();
-    assert!(name != listener.link_name())
+    let err = Link::listen(Protocol::IntraProcess, &name).unwrap_err();
+    assert!(err.to_string().contains("IntraProcess links do not support"));
+}
+
+#[test]
+fn test_intra_process_channel() {
+    let (listener, name) = wstp::intra_process_channel().unwrap();
+
+    assert_eq!(listener.name(), name);
+
+    let connector = std::thread::spawn(move || {
+        let mut link = Link::connect_activated(Protocol::IntraProcess, &name).unwrap();
+        link.put_i64(5).unwrap();
+        link.flush().unwrap();
+        link
+    });
+
+    let mut link = listener.accept().unwrap();
+
+    assert_eq!(link.get_i64(), Ok(5));
+
+    let _connector = connector.join().unwrap();
+}
+
+#[test]
+fn test_available_protocols() {
+    let protocols = wstp::available_protocols();
+
+    // IntraProcess links are supported on every platform.
+    assert!(protocols.contains(&Protocol::IntraProcess));
+}
+
+#[test]
+fn test_ready_parallel() {
+    let mut a = Link::new_loopback().unwrap();
+    let mut b = Link::new_loopback().unwrap();
+    let mut c = Link::new_loopback().unwrap();
+
+    let timeout = std::time::Duration::from_millis(10);
+
+    assert_eq!(wstp::ready_parallel(&mut [&mut a, &mut b, &mut c], timeout), Ok(vec![]));
+    assert_eq!(wstp::ready_parallel(&mut [], timeout), Ok(vec![]));
+
+    a.put_i64(1).unwrap();
+    c.put_i64(3).unwrap();
+
+    assert_eq!(
+        wstp::ready_parallel(&mut [&mut a, &mut b, &mut c], timeout),
+        Ok(vec![0, 2])
+    );
+}
+
+#[test]
+fn test_intra_process_pair_duplex() {
+    let (mut a, mut b) = Link::intra_process_pair().unwrap();
+
+    // Both links are already activated, so data can be exchanged in both directions
+    // from a single thread.
+    for i in 0..3 {
+        a.put_i64(i).unwrap();
+        a.flush().unwrap();
+        assert_eq!(b.get_i64(), Ok(i));
+
+        b.put_i64(-i).unwrap();
+        b.flush().unwrap();
+        assert_eq!(a.get_i64(), Ok(-i));
+    }
+}
+
+#[test]
+fn test_flush_partial_expression() {
+    let (mut writer, mut reader) = Link::intra_process_pair().unwrap();
+
+    writer.put_function("System`List", 3).unwrap();
+    writer.flush().unwrap();
+
+    assert!(reader.wait_ready(std::time::Duration::from_secs(5)).unwrap());
+    assert_eq!(reader.test_head("System`List"), Ok(3));
+
+    for i in 0..3 {
+        assert!(!reader.is_ready());
+
+        writer.put_i64(i).unwrap();
+        writer.flush().unwrap();
+
+        assert!(reader.wait_ready(std::time::Duration::from_secs(5)).unwrap());
+        assert_eq!(reader.get_i64(), Ok(i));
+    }
+}
+
+#[test]
+fn test_intra_process_pair() {
+    let (mut a, mut b) = Link::intra_process_pair().unwrap();
+
+    a.put_str("from a to b").unwrap();
+    a.flush().unwrap();
+
+    b.put_str("from b to a").unwrap();
+    b.flush().unwrap();
+
+    assert_eq!(a.get_string().unwrap(), "from b to a");
+    assert_eq!(b.get_string().unwrap(), "from a to b");
 }
 
 //======================================
@@ -114,6 +214,14 @@ fn test_shared_memory_name_taken_error() {
     assert_eq!(b.unwrap_err().code().unwrap(), sys::MLENAMETAKEN);
 }
 
+#[test]
+fn test_disable_signal_handlers_after_initialization() {
+    // Creating a link initializes the WSTP environment.
+    let _link = Link::new_loopback().unwrap();
+
+    assert!(wstp::disable_signal_handlers(&[2]).is_err());
+}
+
 #[test]
 fn test_link_listen_does_not_block_for_connection() {
     // This test is successful by not hanging.
@@ -150,6 +258,48 @@ fn test_single_thread_duplex_links() {
     assert_eq!(b.get_string().unwrap(), "from a to b");
 }
 
+#[test]
+fn test_connect_activated() {
+    let mut a = Link::listen(Protocol::SharedMemory, "").unwrap();
+    let name = a.link_name();
+
+    let a = std::thread::spawn(move || {
+        a.activate().unwrap();
+        a
+    });
+
+    let mut b = Link::connect_activated(Protocol::SharedMemory, &name).unwrap();
+
+    let mut a = a.join().unwrap();
+
+    a.put_str("from a to b").unwrap();
+    a.flush().unwrap();
+
+    assert_eq!(b.get_string().unwrap(), "from a to b");
+}
+
+#[test]
+fn test_drain() {
+    let (mut a, mut b) = wstp::channel(Protocol::SharedMemory).unwrap();
+
+    a.put_i64(1).unwrap();
+    a.put_str("two").unwrap();
+    a.put_function("System`List", 1).unwrap();
+    a.put_i64(3).unwrap();
+    a.flush().unwrap();
+
+    assert!(b.wait_ready(std::time::Duration::from_secs(5)).unwrap());
+
+    assert_eq!(b.drain(), Ok(3));
+    assert_eq!(b.drain(), Ok(0));
+
+    // The link is still usable after being drained.
+    a.put_i64(4).unwrap();
+    a.flush().unwrap();
+
+    assert_eq!(b.get_i64(), Ok(4));
+}
+
 //======================================
 // TCPIP
 //======================================
@@ -204,6 +354,52 @@ fn test_tcpip_specific_link_creation_methods() {
     check_send_data_across_link(listener, connector);
 }
 
+/// Test that `tcpip_listen()` binds to the interface of the address it is given, and
+/// does not accept connections made to a different interface.
+#[test]
+fn test_tcpip_listen_binds_interface() {
+    use std::time::Duration;
+
+    let _guard = MUTEX.lock().unwrap();
+
+    let mut listener = Link::tcpip_listen("127.0.0.1:8080").unwrap();
+
+    // Try to connect using a different loopback address, which `listener` is not bound
+    // to. (On Linux, all of 127.0.0.0/8 is routed to the loopback interface, so this
+    // is refused rather than unreachable.)
+    let mut connector = Link::tcpip_connect("127.0.0.2:8080").unwrap();
+
+    assert!(connector.activate_timeout(Duration::from_millis(500)).is_err());
+    assert!(listener.activate_timeout(Duration::from_millis(100)).is_err());
+}
+
+/// Test that activating a link fails with a retryable error if the listening side
+/// closes the connection during the handshake.
+#[test]
+fn test_tcpip_activate_closed_mid_handshake_is_retryable() {
+    use std::{net::TcpListener, time::Duration};
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    // Accept the connection, and then immediately close it without completing the
+    // WSTP handshake.
+    let thread = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        drop(stream);
+    });
+
+    let mut connector = Link::tcpip_connect(addr).unwrap();
+
+    let err = connector
+        .activate_timeout(Duration::from_secs(5))
+        .unwrap_err();
+
+    assert!(err.is_retryable(), "error: {:?}", err);
+
+    thread.join().unwrap();
+}
+
 #[test]
 fn test_bug_tcpip_listen_returns_unknown() {
     assert_eq!(
@@ -214,10 +410,43 @@ fn test_bug_tcpip_listen_returns_unknown() {
     );
 }
 
+#[test]
+fn test_tcpip_connect_no_addresses() {
+    let addrs: &[std::net::SocketAddr] = &[];
+
+    let err = Link::tcpip_connect(addrs).unwrap_err();
+
+    assert_eq!(err.code(), None);
+    assert!(err
+        .to_string()
+        .contains("the address did not resolve to any socket addresses"));
+
+    // Host names are included in the error, whether or not resolving them fails.
+    // (The .invalid top-level domain is reserved, and never resolves.)
+    let err = Link::tcpip_connect("wstp-rs.invalid:8080").unwrap_err();
+
+    assert_eq!(err.code(), None);
+    assert!(err.to_string().contains("wstp-rs.invalid:8080"));
+
+    let err = Link::tcpip_connect(("wstp-rs.invalid", 8080)).unwrap_err();
+
+    assert!(err.to_string().contains("wstp-rs.invalid:8080"));
+}
+
 //======================================
 // Misc.
 //======================================
 
+#[test]
+fn test_raw_stdenv() {
+    let raw_env: sys::WSENV = unsafe { wstp::raw_stdenv() }.unwrap();
+
+    assert!(!raw_env.is_null());
+
+    // The same environment is returned each time.
+    assert_eq!(unsafe { wstp::raw_stdenv() }, Ok(raw_env));
+}
+
 //-------------------------------------
 // Test wait() and wait_with_callback()
 //-------------------------------------
@@ -287,6 +516,142 @@ fn test_link_wait_with_callback_drops_closure() {
     assert_eq!(Arc::strong_count(&data), 1);
 }
 
+#[test]
+fn test_link_wait_timeout() {
+    use std::time::{Duration, Instant};
+    use wstp::Timeout;
+
+    let mut listener = Link::listen(Protocol::IntraProcess, "").unwrap();
+
+    let timeout = Timeout::new(Duration::from_millis(50))
+        .with_poll_interval(Duration::from_millis(1));
+
+    let start = Instant::now();
+    assert_eq!(listener.wait_timeout(timeout), Ok(false));
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+fn test_link_activate_timeout() {
+    use std::time::{Duration, Instant};
+
+    // A listener that nothing ever connects to.
+    let mut listener = Link::listen(Protocol::SharedMemory, "").unwrap();
+
+    let start = Instant::now();
+    let err = listener
+        .activate_timeout(Duration::from_millis(50))
+        .unwrap_err();
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    assert!(err.to_string().contains("timed out"), "{}", err);
+    assert!(!listener.is_activated());
+
+    // A link that is connected to activates normally.
+    let (mut a, mut b) = wstp::channel(Protocol::IntraProcess).unwrap();
+    a.activate_timeout(Duration::from_secs(5)).unwrap();
+    b.activate_timeout(Duration::from_secs(5)).unwrap();
+}
+
+#[test]
+fn test_is_activated() {
+    use std::time::Duration;
+
+    let mut listener = Link::listen(Protocol::IntraProcess, "").unwrap();
+    let mut connector =
+        Link::connect(Protocol::IntraProcess, &listener.link_name()).unwrap();
+
+    assert!(!listener.is_activated());
+    assert!(!connector.is_activated());
+
+    let thread = std::thread::spawn(move || {
+        listener.activate_timeout(Duration::from_secs(5)).unwrap();
+        listener
+    });
+
+    connector.activate().unwrap();
+    let listener = thread.join().unwrap();
+
+    assert!(listener.is_activated());
+    assert!(connector.is_activated());
+
+    // Links returned by convenience constructors are already activated.
+    let (a, b) = wstp::channel(Protocol::SharedMemory).unwrap();
+    assert!(a.is_activated() && b.is_activated());
+}
+
+#[test]
+fn test_is_peer_present() {
+    let listener = Link::listen(Protocol::IntraProcess, "").unwrap();
+
+    // Nothing has connected to `listener` yet.
+    assert!(!listener.is_peer_present());
+
+    let connector = Link::connect(Protocol::IntraProcess, &listener.link_name()).unwrap();
+
+    // An unactivated connecting link can't tell whether its peer is present.
+    assert!(connector.is_peer_present());
+
+    // Pending TCPIP connections are not reported before activation.
+    let tcpip_listener = Link::listen(Protocol::TCPIP, "8091").unwrap();
+    assert!(tcpip_listener.is_peer_present());
+
+    let (a, b) = wstp::channel(Protocol::SharedMemory).unwrap();
+    assert!(a.is_peer_present() && b.is_peer_present());
+}
+
+#[test]
+fn test_link_wait_ready() {
+    use std::time::Duration;
+
+    let (mut a, mut b) = wstp::channel(Protocol::SharedMemory).unwrap();
+
+    assert_eq!(b.wait_ready(Duration::from_millis(20)), Ok(false));
+
+    let writer = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+
+        a.put_i64(5).unwrap();
+        a.flush().unwrap();
+
+        a
+    });
+
+    assert_eq!(b.wait_ready(Duration::from_secs(5)), Ok(true));
+    assert_eq!(b.get_i64(), Ok(5));
+
+    let _a = writer.join().unwrap();
+}
+
+#[test]
+fn test_link_get_expr_timeout() {
+    use std::time::Duration;
+    use wolfram_expr::{Expr, Symbol};
+
+    let (mut a, mut b) = wstp::channel(Protocol::SharedMemory).unwrap();
+
+    let list = Expr::normal(Symbol::new("System`List"), vec![Expr::from(1)]);
+
+    assert_eq!(b.get_expr_timeout(Duration::from_millis(20)), Ok(None));
+
+    let writer = {
+        let list = list.clone();
+
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+
+            a.put_expr(&list).unwrap();
+            a.flush().unwrap();
+
+            a
+        })
+    };
+
+    assert_eq!(b.get_expr_timeout(Duration::from_secs(5)), Ok(Some(list)));
+
+    let _a = writer.join().unwrap();
+}
+
 #[test]
 fn test_link_wait_with_callback_nested() {
     let mut listener = Link::listen(Protocol::IntraProcess, "").unwrap();
@@ -378,6 +743,70 @@ fn test_mismatched_array_type_rounding() {
     assert_eq!(out.data(), &[3, 2, 3]);
 }
 
+#[test]
+fn test_complex_array() {
+    let mut link = Link::new_loopback().unwrap();
+
+    let data = [
+        (1.0, 2.0),
+        (3.0, -4.0),
+        (0.5, 0.0),
+        (-1.0, 1.0),
+        (0.0, 0.0),
+        (2.0, 2.0),
+    ];
+
+    link.put_complex_f64_array(&data, &[2, 3]).unwrap();
+
+    assert_eq!(link.get_complex_f64_array(), Ok((data.to_vec(), vec![2, 3])));
+
+    // Integer arrays are widened to complex arrays.
+    link.put_i64_array(&[1, 2, 3, 4], &[2, 2]).unwrap();
+
+    assert_eq!(
+        link.get_complex_f64_array(),
+        Ok((vec![(1.0, 0.0), (2.0, 0.0), (3.0, 0.0), (4.0, 0.0)], vec![2, 2]))
+    );
+
+    // Symbols without a context, as sent by the Kernel, are resolved in System`.
+    link.put_function("List", 1).unwrap();
+    link.put_function("Complex", 2).unwrap();
+    link.put_f64(1.0).unwrap();
+    link.put_f64(2.0).unwrap();
+
+    assert_eq!(link.get_complex_f64_array(), Ok((vec![(1.0, 2.0)], vec![1])));
+
+    // Empty arrays keep their dimensions.
+    link.put_complex_f64_array(&[], &[2, 0]).unwrap();
+
+    assert_eq!(link.get_complex_f64_array(), Ok((vec![], vec![2, 0])));
+
+    // Ragged arrays are an error.
+    link.put_function("System`List", 2).unwrap();
+    link.put_function("System`List", 1).unwrap();
+    link.put_f64(1.0).unwrap();
+    link.put_function("System`List", 0).unwrap();
+
+    assert!(link.get_complex_f64_array().is_err());
+}
+
+// Test that the exact array functions refuse to convert element types.
+#[test]
+fn test_mismatched_array_type_exact() {
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_f64_array(&[3.141, 1.618, 2.718], &[3]).unwrap();
+
+    assert!(link.get_i64_array_exact().is_err());
+    assert_eq!(link.get_f64_array_exact().unwrap().data(), &[3.141, 1.618, 2.718]);
+
+    link.put_i64_array(&[1, 2, 300], &[3]).unwrap();
+
+    assert!(link.get_f64_array_exact().is_err());
+    assert!(link.get_u8_array_exact().is_err());
+    assert_eq!(link.get_i16_array_exact().unwrap().data(), &[1, 2, 300]);
+}
+
 // Test that reading an f64 array as a scalar i64 results in an get sequence error.
 #[test]
 fn test_mismatched_type_error() {
@@ -391,6 +820,116 @@ fn test_mismatched_type_error() {
     );
 }
 
+//--------------------------------------
+// Test sending packets
+//--------------------------------------
+
+#[test]
+fn test_send_expr() {
+    use wolfram_expr::{Expr, Symbol};
+
+    let (mut a, mut b) = wstp::channel(Protocol::SharedMemory).unwrap();
+
+    let packet = Expr::normal(
+        Symbol::new("System`EvaluatePacket"),
+        vec![Expr::normal(
+            Symbol::new("System`Plus"),
+            vec![Expr::from(1), Expr::from(1)],
+        )],
+    );
+
+    // No explicit flush() is needed.
+    a.send_expr(&packet).unwrap();
+
+    assert!(b.wait_ready(std::time::Duration::from_secs(5)).unwrap());
+    assert_eq!(b.get_expr(), Ok(packet));
+}
+
+#[test]
+fn test_send_packet() {
+    let (mut a, mut b) = wstp::channel(Protocol::SharedMemory).unwrap();
+
+    a.put_function("System`ReturnPacket", 1).unwrap();
+    a.put_i64(1).unwrap();
+
+    // No explicit flush() is needed.
+    a.send_packet().unwrap();
+
+    assert!(b.wait_ready(std::time::Duration::from_secs(5)).unwrap());
+    assert_eq!(b.receive_expr(), Ok(wolfram_expr::Expr::from(1)));
+}
+
+#[test]
+fn test_receive_expr() {
+    use wolfram_expr::Expr;
+
+    let (mut a, mut b) = wstp::channel(Protocol::SharedMemory).unwrap();
+
+    // ReturnPacket[1, "extra"]
+    a.put_function("ReturnPacket", 2).unwrap();
+    a.put_i64(1).unwrap();
+    a.put_str("extra").unwrap();
+    a.end_packet().unwrap();
+
+    // ReturnPacket[2]
+    a.put_function("ReturnPacket", 1).unwrap();
+    a.put_i64(2).unwrap();
+    a.end_packet().unwrap();
+    a.flush().unwrap();
+
+    // The rest of the first packet is discarded.
+    assert_eq!(b.receive_expr(), Ok(Expr::from(1)));
+    assert_eq!(b.receive_expr(), Ok(Expr::from(2)));
+}
+
+//--------------------------------------
+// Test flushing
+//--------------------------------------
+
+#[test]
+fn test_flush_and_sync() {
+    let (mut a, mut b) = wstp::channel(Protocol::SharedMemory).unwrap();
+
+    a.put_str("hello").unwrap();
+    a.flush_and_sync().unwrap();
+
+    assert_eq!(a.bytes_to_put(), Ok(0));
+
+    // The data should still be readable after the writing link is closed.
+    drop(a);
+
+    assert_eq!(b.get_string(), Ok(String::from("hello")));
+}
+
+#[test]
+fn test_try_flush() {
+    let (mut a, mut b) = wstp::channel(Protocol::SharedMemory).unwrap();
+
+    a.put_str("hello").unwrap();
+
+    while !a.try_flush().unwrap() {
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+
+    assert_eq!(a.bytes_to_put(), Ok(0));
+    assert_eq!(b.get_string(), Ok(String::from("hello")));
+}
+
+#[test]
+fn test_bytes_to_get() {
+    let (mut a, mut b) = wstp::channel(Protocol::SharedMemory).unwrap();
+
+    assert_eq!(b.bytes_to_get(), Ok(0));
+
+    a.put_str("hello").unwrap();
+    a.flush().unwrap();
+
+    assert!(b.wait_ready(std::time::Duration::from_secs(5)).unwrap());
+    assert!(b.bytes_to_get().unwrap() > 0);
+
+    assert_eq!(b.get_string(), Ok(String::from("hello")));
+}
+
 //--------------------------------------
 // Test sending urgent messages
 //--------------------------------------
@@ -1,12 +1,8 @@
-use wolfram_expr::{Expr, Symbol};
+use wolfram_expr::{symbol::Context, Expr, Symbol};
 use wstp::{sys, Link, LinkStr, Protocol, Token, TokenType};
 
 fn check_loopback_roundtrip(expr: Expr) {
-    let mut link = Link::new_loopback().expect("failed to create Loopback link");
-
-    link.put_expr(&expr).expect("failed to write expr");
-
-    let read = link.get_expr().expect("failed to read expr");
+    let read = wstp::roundtrip_expr(&expr).expect("failed to round trip expr");
 
     assert_eq!(expr, read);
 }
@@ -49,7 +45,7 @@ fn test_is_loopback() {
     let link = Link::new_loopback().unwrap();
     assert!(link.is_loopback());
 
-    let link = Link::listen(Protocol::IntraProcess, "name-test").unwrap();
+    let link = Link::listen(Protocol::IntraProcess, "").unwrap();
     assert!(!link.is_loopback());
 }
 
@@ -123,9 +119,17 @@ fn test_loopback_get_next() {
     assert_eq!(link.raw_get_next(), Ok(sys::WSTKSYM.into()));
     assert_eq!(link.raw_get_next(), Ok(sys::WSTKINT.into()));
 
-    assert_eq!(link.raw_get_next().unwrap_err().code(), Some(sys::WSEABORT));
+    let err = link.raw_get_next().unwrap_err();
+    assert_eq!(err.code(), Some(sys::WSEABORT));
+    assert!(err.is_end_of_data());
+    assert!(err.with_context("reading").is_end_of_data());
 
     assert!(!link.is_ready());
+
+    // Other errors are not the end of the data.
+    link.clear_error();
+    link.put_str("not an integer").unwrap();
+    assert!(!link.get_i64().unwrap_err().is_end_of_data());
 }
 
 #[test]
@@ -168,6 +172,29 @@ fn test_loopback_test_head_error() {
     );
 }
 
+#[test]
+fn test_loopback_get_args_of() {
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_function("System`List", 2).unwrap();
+    link.put_i64(1).unwrap();
+    link.put_symbol("Global`x").unwrap();
+
+    assert_eq!(
+        link.get_args_of("System`List"),
+        Ok(vec![Expr::from(1), Expr::symbol(Symbol::new("Global`x"))])
+    );
+
+    link.put_function("System`List", 0).unwrap();
+
+    let err = link.get_args_of("System`Plot").unwrap_err();
+
+    assert_eq!(err.code(), Some(sys::WSEGSEQ));
+    assert!(err
+        .to_string()
+        .contains("get_args_of: expected function with head System`Plot"));
+}
+
 #[test]
 fn test_loopback_transfer_simple() {
     let mut link = Link::new_loopback().unwrap();
@@ -191,7 +218,7 @@ fn test_loopback_transfer_list() {
     link.transfer_to_end_of_loopback_link(&mut new).unwrap();
 
     assert_eq!(
-        new.get_expr().unwrap().to_string(),
+        new.dump_remaining().unwrap(),
         "System`List[5, \"second\", Global`foo]"
     );
 }
@@ -243,3 +270,1361 @@ fn test_loopback_get_token_type_is_idempotent() {
 
     assert!(matches!(link.get_token().unwrap(), Token::Integer(5)));
 }
+
+#[test]
+fn test_loopback_get_symbol() {
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_symbol("System`Plot").unwrap();
+    link.put_symbol("x").unwrap();
+    link.put_symbol("x").unwrap();
+
+    assert_eq!(link.get_symbol(), Ok(Symbol::new("System`Plot")));
+    assert_eq!(
+        link.get_symbol().unwrap_err().to_string(),
+        "WSTP error: symbol name 'x' has no context"
+    );
+    assert_eq!(
+        link.get_symbol_in_context(&Context::global()),
+        Ok(Symbol::new("Global`x"))
+    );
+}
+
+#[test]
+fn test_loopback_symbol_special_characters() {
+    let symbols = [
+        "Global`x$123",
+        "System`$Failed",
+        "Global`αβγ",
+        "Global`Private`x",
+        "Global`\u{1D538}",
+    ];
+
+    let mut link = Link::new_loopback().unwrap();
+
+    for symbol in symbols {
+        link.put_symbol(symbol).unwrap();
+        assert_eq!(link.get_symbol_ref().unwrap().as_str(), symbol);
+
+        link.put_expr(&Expr::symbol(Symbol::new(symbol))).unwrap();
+        assert_eq!(link.get_expr(), Ok(Expr::symbol(Symbol::new(symbol))));
+    }
+}
+
+#[test]
+fn test_loopback_get_utf8_str_chunks() {
+    let mut link = Link::new_loopback().unwrap();
+
+    let string: String = "αβγ Hello, World! ".repeat(100);
+
+    link.put_str(&string).unwrap();
+    link.put_i64(5).unwrap();
+
+    let mut bytes: Vec<u8> = Vec::new();
+
+    link.get_utf8_str_chunks(7, |chunk| {
+        assert!(!chunk.is_empty() && chunk.len() <= 7);
+        bytes.extend_from_slice(chunk);
+    })
+    .unwrap();
+
+    assert_eq!(String::from_utf8(bytes).unwrap(), string);
+
+    // Data after the string is unaffected.
+    assert_eq!(link.get_i64(), Ok(5));
+}
+
+#[test]
+fn test_loopback_put_nested_list() {
+    let mut link = Link::new_loopback().unwrap();
+
+    let rows: Vec<Vec<i64>> = vec![vec![1, 2, 3], vec![], vec![4]];
+
+    link.put_nested_list(&rows, |link, &value| link.put_i64(value))
+        .unwrap();
+
+    assert_eq!(
+        link.get_expr().unwrap(),
+        Expr::list(vec![
+            Expr::list(vec![Expr::from(1i64), Expr::from(2i64), Expr::from(3i64)]),
+            Expr::list(vec![]),
+            Expr::list(vec![Expr::from(4i64)]),
+        ])
+    );
+
+    // Empty outer list
+    let rows: Vec<Vec<String>> = vec![];
+
+    link.put_nested_list(&rows, |link, value| link.put_str(value))
+        .unwrap();
+
+    assert_eq!(link.get_expr().unwrap(), Expr::list(vec![]));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_loopback_datetime() {
+    use chrono::{TimeZone, Timelike, Utc};
+
+    let mut link = Link::new_loopback().unwrap();
+
+    let datetime = Utc
+        .with_ymd_and_hms(2022, 3, 14, 15, 9, 26)
+        .unwrap()
+        .with_nanosecond(500_000_000)
+        .unwrap();
+
+    link.put_datetime(datetime).unwrap();
+    assert_eq!(link.get_datetime(), Ok(datetime));
+
+    // DateObject in a non-UTC time zone.
+    link.put_function("System`DateObject", 4).unwrap();
+    link.put_function("System`List", 6).unwrap();
+    for value in [2022, 3, 14, 10, 9] {
+        link.put_i64(value).unwrap();
+    }
+    link.put_f64(26.0).unwrap();
+    link.put_str("Instant").unwrap();
+    link.put_str("Gregorian").unwrap();
+    link.put_f64(-5.0).unwrap();
+
+    assert_eq!(
+        link.get_datetime(),
+        Ok(Utc.with_ymd_and_hms(2022, 3, 14, 15, 9, 26).unwrap())
+    );
+
+    // DateObject with only a date list.
+    link.put_function("System`DateObject", 1).unwrap();
+    link.put_function("System`List", 3).unwrap();
+    for value in [2022, 3, 14] {
+        link.put_i64(value).unwrap();
+    }
+
+    assert_eq!(
+        link.get_datetime(),
+        Ok(Utc.with_ymd_and_hms(2022, 3, 14, 0, 0, 0).unwrap())
+    );
+
+    // DateObjects sent by the Kernel use System` symbols without a context.
+    link.put_function("DateObject", 1).unwrap();
+    link.put_function("List", 2).unwrap();
+    link.put_i64(2022).unwrap();
+    link.put_i64(3).unwrap();
+
+    assert_eq!(
+        link.get_datetime(),
+        Ok(Utc.with_ymd_and_hms(2022, 3, 1, 0, 0, 0).unwrap())
+    );
+}
+
+#[test]
+fn test_loopback_association() {
+    use std::collections::{BTreeMap, HashMap};
+
+    let mut link = Link::new_loopback().unwrap();
+
+    let map: HashMap<String, i64> =
+        HashMap::from([("a".to_owned(), 1), ("b".to_owned(), 2), ("c".to_owned(), 3)]);
+
+    link.put_association(
+        &map,
+        |link, key| link.put_str(key),
+        |link, &value| link.put_i64(value),
+    )
+    .unwrap();
+
+    let read: HashMap<String, i64> =
+        link.get_association(Link::get_string, Link::get_i64).unwrap();
+
+    assert_eq!(read, map);
+
+    // BTreeMap keys are written in sorted order.
+    let map = BTreeMap::from([(3, "c"), (1, "a"), (2, "b")]);
+
+    link.put_association(
+        &map,
+        |link, &key| link.put_i64(key),
+        |link, value| link.put_str(value),
+    )
+    .unwrap();
+
+    assert_eq!(
+        link.get_expr().unwrap().to_string(),
+        "System`Association[System`Rule[1, \"a\"], System`Rule[2, \"b\"], System`Rule[3, \"c\"]]"
+    );
+
+    // Associations sent by the Kernel use System` symbols without a context.
+    link.put_function("Association", 1).unwrap();
+    link.put_function("Rule", 2).unwrap();
+    link.put_str("key").unwrap();
+    link.put_str("value").unwrap();
+
+    let map: BTreeMap<String, String> =
+        link.get_association(Link::get_string, Link::get_string).unwrap();
+
+    assert_eq!(map, BTreeMap::from([("key".to_owned(), "value".to_owned())]));
+
+    // Association elements must be rules.
+    link.put_function("System`Association", 1).unwrap();
+    link.put_i64(5).unwrap();
+
+    assert!(link
+        .get_association::<Vec<_>, _, _, _, _>(Link::get_i64, Link::get_i64)
+        .is_err());
+}
+
+#[test]
+fn test_loopback_map_to_link() {
+    use std::collections::{BTreeMap, HashMap};
+    use wstp::{FromLink, ToLink};
+
+    let mut link = Link::new_loopback().unwrap();
+
+    let map: HashMap<String, Vec<i64>> =
+        HashMap::from([("a".to_owned(), vec![1, 2]), ("b".to_owned(), vec![])]);
+
+    map.to_link(&mut link).unwrap();
+
+    assert_eq!(HashMap::from_link(&mut link), Ok(map));
+
+    // BTreeMap keys are written in sorted order.
+    let map = BTreeMap::from([("y", 2.5), ("x", 1.5)]);
+
+    map.to_link(&mut link).unwrap();
+
+    assert_eq!(
+        link.get_expr().unwrap().to_string(),
+        "System`Association[System`Rule[\"x\", 1.5], System`Rule[\"y\", 2.5]]"
+    );
+
+    // Associations sent by the Kernel use System` symbols without a context.
+    link.put_function("Association", 1).unwrap();
+    link.put_function("Rule", 2).unwrap();
+    link.put_i64(1).unwrap();
+    link.put_symbol("True").unwrap();
+
+    assert_eq!(
+        BTreeMap::from_link(&mut link),
+        Ok(BTreeMap::from([(1i64, true)]))
+    );
+}
+
+#[test]
+fn test_loopback_put_association_with() {
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_association_with(|assoc| {
+        for (index, key) in ["x", "y", "z"].iter().enumerate() {
+            assoc.rule(key, |link| link.put_i64(index as i64))?;
+        }
+
+        assoc.rule_with(
+            |link| link.put_i64(10),
+            |link| link.put_association_with(|_| Ok(())),
+        )?;
+
+        Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(
+        link.dump_remaining().unwrap(),
+        "System`Association[System`Rule[\"x\", 0], System`Rule[\"y\", 1], \
+         System`Rule[\"z\", 2], System`Rule[10, System`Association[]]]"
+    );
+
+    // Nothing is written if building the association fails.
+    let result = link.put_association_with(|assoc| {
+        assoc.rule("a", |link| link.put_i64(1))?;
+        // Reading from the buffer fails, because it contains a Rule[..] expression.
+        assoc.rule("b", |link| link.get_i64().map(drop))?;
+
+        Ok(())
+    });
+
+    assert!(result.is_err());
+    assert!(!link.is_ready());
+}
+
+#[test]
+fn test_loopback_get_optional() {
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_null().unwrap();
+    link.put_symbol("Null").unwrap();
+    link.put_missing(None).unwrap();
+    link.put_missing(Some("NotFound")).unwrap();
+    link.put_str("value").unwrap();
+    link.put_symbol("Global`foo").unwrap();
+
+    assert_eq!(link.get_optional(Link::get_string), Ok(None));
+    assert_eq!(link.get_optional(Link::get_string), Ok(None));
+    assert_eq!(link.get_optional(Link::get_string), Ok(None));
+    assert_eq!(link.get_optional(Link::get_string), Ok(None));
+    assert_eq!(
+        link.get_optional(Link::get_string),
+        Ok(Some(String::from("value")))
+    );
+
+    // Symbols other than Null are left on the link to be read by `get`.
+    assert_eq!(
+        link.get_optional(|link| link.get_expr()),
+        Ok(Some(Expr::symbol(Symbol::new("Global`foo"))))
+    );
+}
+
+#[test]
+fn test_loopback_peek_count() {
+    let mut link = Link::new_loopback().unwrap();
+
+    assert_eq!(link.peek_count(), Ok(0));
+
+    link.put_str("a").unwrap();
+    link.put_function("System`List", 1).unwrap();
+    link.put_i64(10).unwrap();
+    link.put_symbol("Global`b").unwrap();
+
+    // Counting does not consume any expressions.
+    assert_eq!(link.peek_count(), Ok(3));
+    assert_eq!(link.peek_count(), Ok(3));
+
+    assert_eq!(link.get_string(), Ok(String::from("a")));
+    assert_eq!(link.peek_count(), Ok(2));
+}
+
+#[test]
+fn test_loopback_put_expr_limited() {
+    let mut link = Link::new_loopback().unwrap();
+
+    // {{{1}}}
+    let mut expr = Expr::from(1i64);
+    for _ in 0..3 {
+        expr = Expr::list(vec![expr]);
+    }
+
+    assert!(link.put_expr_limited(&expr, 3).is_err());
+
+    // Nothing should have been written to the link.
+    assert!(!link.is_ready());
+
+    link.put_expr_limited(&expr, 4).unwrap();
+
+    assert_eq!(link.get_expr(), Ok(expr));
+}
+
+/// Test that reading a very deeply nested expression returns an error instead of
+/// overflowing the stack.
+#[test]
+fn test_loopback_get_expr_deeply_nested() {
+    let mut link = Link::new_loopback().unwrap();
+
+    for _ in 0..100_000 {
+        link.put_function("System`List", 1).unwrap();
+    }
+    link.put_i64(1).unwrap();
+
+    let err = link.get_expr().unwrap_err();
+
+    assert!(err.code().is_none());
+    assert_eq!(
+        err.to_string(),
+        "WSTP error: expression depth exceeds maximum depth of 1024"
+    );
+}
+
+#[test]
+fn test_loopback_get_expr_limited() {
+    let mut link = Link::new_loopback().unwrap();
+
+    // {{1}, foo[]}
+    let expr = Expr::list(vec![
+        Expr::list(vec![Expr::from(1i64)]),
+        Expr::normal(Symbol::new("Global`foo"), vec![]),
+    ]);
+
+    link.put_expr(&expr).unwrap();
+    assert!(link.get_expr_limited(2).is_err());
+
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_expr(&expr).unwrap();
+    assert_eq!(link.get_expr_limited(3), Ok(expr));
+}
+
+#[test]
+fn test_loopback_put_expr_fast() {
+    let mut link = Link::new_loopback().unwrap();
+
+    let expr = Expr::list(vec![
+        Expr::list((0..1000i64).map(Expr::from).collect()),
+        Expr::list(vec![Expr::real(1.5), Expr::real(2.5)]),
+        // Mixed lists are written element by element.
+        Expr::list(vec![Expr::from(1i64), Expr::real(2.5), Expr::string("a")]),
+        Expr::list(vec![]),
+        Expr::normal(Symbol::new("Global`foo"), vec![Expr::from(1i64)]),
+    ]);
+
+    link.put_expr_fast(&expr).unwrap();
+
+    assert_eq!(link.get_expr(), Ok(expr));
+}
+
+#[test]
+fn test_loopback_put_bytes() {
+    let expr = Expr::normal(
+        Symbol::new("Global`f"),
+        vec![
+            Expr::from(1),
+            Expr::real(2.5),
+            Expr::string("αβγ"),
+            Expr::normal(Expr::symbol(Symbol::new("Global`g")), vec![]),
+        ],
+    );
+
+    let blob = Link::to_bytes(&expr).unwrap();
+
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_bytes(&blob).unwrap();
+    link.put_bytes(&blob).unwrap();
+
+    assert_eq!(link.get_expr(), Ok(expr.clone()));
+    assert_eq!(link.get_expr(), Ok(expr));
+
+    // Truncated blobs are rejected without writing anything.
+    assert!(link.put_bytes(&blob[..blob.len() - 1]).is_err());
+    assert!(!link.is_ready());
+}
+
+#[test]
+fn test_expr_from_bytes() {
+    let expr = Expr::normal(
+        Symbol::new("System`List"),
+        vec![Expr::from(-5), Expr::symbol(Symbol::new("Global`x$123"))],
+    );
+
+    let blob = Link::to_bytes(&expr).unwrap();
+
+    assert_eq!(wstp::expr_from_bytes(&blob), Ok(expr));
+
+    // Every truncation of a valid blob is an error.
+    for len in 0..blob.len() {
+        assert!(wstp::expr_from_bytes(&blob[..len]).is_err());
+    }
+
+    // Trailing data is an error.
+    let mut extended = blob.clone();
+    extended.push(0);
+    assert!(wstp::expr_from_bytes(&extended).is_err());
+
+    assert!(wstp::expr_from_bytes(b"").is_err());
+    assert!(wstp::expr_from_bytes(&[0xFF; 64]).is_err());
+}
+
+#[test]
+fn test_take_expr_bytes() {
+    let expr = Expr::normal(
+        Symbol::new("Global`f"),
+        vec![Expr::string("x"), Expr::real(1.5)],
+    );
+
+    let mut a = Link::new_loopback().unwrap();
+
+    a.put_expr(&expr).unwrap();
+    a.put_i64(7).unwrap();
+
+    // Only the first expression is taken from the link.
+    let blob = a.take_expr_bytes().unwrap();
+    assert_eq!(a.get_i64(), Ok(7));
+
+    assert_eq!(blob, Link::to_bytes(&expr).unwrap());
+
+    let mut b = Link::new_loopback().unwrap();
+
+    b.put_bytes(&blob).unwrap();
+
+    assert_eq!(b.get_expr(), Ok(expr));
+}
+
+#[test]
+fn test_loopback_max_array_elements() {
+    let mut link = Link::new_loopback().unwrap();
+
+    assert_eq!(link.max_array_elements(), None);
+
+    link.set_max_array_elements(6);
+    assert_eq!(link.max_array_elements(), Some(6));
+
+    link.put_i64_array(&[1, 2, 3, 4, 5, 6], &[2, 3]).unwrap();
+    link.put_f64_array(&[1.0; 8], &[2, 2, 2]).unwrap();
+
+    assert_eq!(link.get_i64_array().unwrap().dimensions(), &[2, 3]);
+
+    // The rejected array is left on the link.
+    let err = link.get_f64_array().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "WSTP error: array with 8 elements exceeds the limit of 6 elements"
+    );
+    assert!(link.get_complex_f64_array().is_err());
+    assert!(link.get_f64_array_exact().is_err());
+
+    link.set_max_array_elements(usize::MAX);
+    assert_eq!(link.max_array_elements(), None);
+
+    assert_eq!(link.get_f64_array().unwrap().data(), &[1.0; 8]);
+}
+
+#[test]
+fn test_max_array_elements_borrowed() {
+    let mut link = Link::new_loopback().unwrap();
+    let mut raw_link = unsafe { link.raw_link() };
+
+    // A limit set through a borrowed link is removed when the borrow ends.
+    unsafe {
+        Link::with_borrowed(&mut raw_link, |borrowed| {
+            borrowed.set_max_array_elements(1);
+            assert_eq!(borrowed.max_array_elements(), Some(1));
+        })
+    };
+
+    assert_eq!(link.max_array_elements(), None);
+
+    // A limit set by the owner is visible through, and kept after, a borrow.
+    link.set_max_array_elements(2);
+
+    unsafe {
+        Link::with_borrowed(&mut raw_link, |borrowed| {
+            assert_eq!(borrowed.max_array_elements(), Some(2));
+            borrowed.set_max_array_elements(usize::MAX);
+        })
+    };
+
+    assert_eq!(link.max_array_elements(), Some(2));
+
+    // A limit left on a WSLINK whose `Link` is never dropped does not apply to a new
+    // `Link` that takes ownership of it.
+    std::mem::forget(link);
+
+    let link = unsafe { Link::unchecked_new(raw_link) };
+
+    assert_eq!(link.max_array_elements(), None);
+}
+
+#[test]
+fn test_loopback_i8_roundtrip() {
+    let mut link = Link::new_loopback().unwrap();
+
+    for value in i8::MIN..=i8::MAX {
+        link.put_i8(value).unwrap();
+        assert_eq!(link.get_i8(), Ok(value));
+    }
+
+    // Negative values are received as negative integers.
+    link.put_i8(-1).unwrap();
+    assert_eq!(link.get_i64(), Ok(-1));
+
+    link.put_i64(200).unwrap();
+    assert!(link.get_i8().is_err());
+}
+
+#[test]
+fn test_loopback_peek_array_type() {
+    use wstp::ArrayElementType;
+
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_i16_array(&[1, 2, 3, 4], &[2, 2]).unwrap();
+    link.put_f32_array(&[1.0, 2.0], &[2]).unwrap();
+    link.put_function("System`List", 0).unwrap();
+    link.put_i64(5).unwrap();
+
+    assert_eq!(link.peek_array_type(), Ok(Some(ArrayElementType::Integer)));
+    assert_eq!(link.get_i16_array().unwrap().dimensions(), &[2, 2]);
+
+    assert_eq!(link.peek_array_type(), Ok(Some(ArrayElementType::Real)));
+    assert_eq!(link.get_f32_array().unwrap().data(), &[1.0, 2.0]);
+
+    // Empty lists and non-lists have no element type.
+    assert_eq!(link.peek_array_type(), Ok(None));
+    assert_eq!(link.test_head("System`List"), Ok(0));
+
+    assert_eq!(link.peek_array_type(), Ok(None));
+    assert_eq!(link.get_i64(), Ok(5));
+}
+
+#[test]
+fn test_empty_list_round_trip() {
+    use wstp::FromLink;
+
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_empty_list().unwrap();
+    link.put_empty_list().unwrap();
+
+    assert_eq!(
+        link.get_expr(),
+        Ok(Expr::normal(Symbol::new("System`List"), vec![]))
+    );
+
+    // No elements are read after the List[] head.
+    assert_eq!(Vec::<i64>::from_link(&mut link), Ok(vec![]));
+    assert!(link.raw_get_next().unwrap_err().is_end_of_data());
+}
+
+#[test]
+fn test_get_all_exprs() {
+    let mut link = Link::new_loopback().unwrap();
+
+    assert_eq!(link.get_all_exprs(), Ok(vec![]));
+
+    let exprs = vec![
+        Expr::from(1),
+        Expr::normal(Symbol::new("Global`f"), vec![Expr::string("x")]),
+        Expr::real(2.5),
+    ];
+
+    for expr in &exprs {
+        link.put_expr(expr).unwrap();
+    }
+
+    assert_eq!(link.get_all_exprs(), Ok(exprs));
+    assert_eq!(link.get_all_exprs(), Ok(vec![]));
+
+    // An incomplete expression is an error.
+    link.put_function("System`List", 2).unwrap();
+    link.put_i64(1).unwrap();
+
+    let err = link.get_all_exprs().unwrap_err();
+    assert!(err.to_string().contains("expression at index 0"));
+}
+
+#[test]
+fn test_set_description() {
+    let mut link = Link::new_loopback().unwrap();
+
+    assert_eq!(link.description(), None);
+    assert!(!link.to_string().contains("description"));
+
+    let name = link.link_name();
+
+    link.set_description("my-buffer");
+
+    assert_eq!(link.description().as_deref(), Some("my-buffer"));
+    assert!(link.to_string().contains("description=\"my-buffer\""));
+
+    // The WSTP link name is unchanged.
+    assert_eq!(link.link_name(), name);
+
+    // Descriptions are per-link.
+    let other = Link::new_loopback().unwrap();
+    assert_eq!(other.description(), None);
+
+    // The description is not inherited by a new `Link` that owns the same WSLINK.
+    let raw_link = unsafe { link.raw_link() };
+    std::mem::forget(link);
+
+    let link = unsafe { Link::unchecked_new(raw_link) };
+    assert_eq!(link.description(), None);
+}
+
+#[test]
+fn test_get_expr_with_context_policy() {
+    use wstp::{ContextPolicy, GetExprConfig};
+
+    let mut link = Link::new_loopback().unwrap();
+
+    // f[x]
+    let put_fx = |link: &mut Link| {
+        link.put_function("f", 1).unwrap();
+        link.put_symbol("x").unwrap();
+    };
+
+    // ContextPolicy::Error matches Link::get_expr().
+    put_fx(&mut link);
+    assert_eq!(
+        link.get_expr_with(&GetExprConfig::default())
+            .unwrap_err()
+            .to_string(),
+        "WSTP error: symbol name 'f' has no context"
+    );
+    link.new_packet().unwrap();
+
+    put_fx(&mut link);
+    let config = GetExprConfig {
+        unresolved_context: ContextPolicy::DefaultTo(Context::new("MyContext`")),
+        ..GetExprConfig::default()
+    };
+    assert_eq!(
+        link.get_expr_with(&config),
+        Ok(Expr::normal(
+            Symbol::new("MyContext`f"),
+            vec![Expr::symbol(Symbol::new("MyContext`x"))]
+        ))
+    );
+
+    // Symbols that already have a context are not affected.
+    link.put_symbol("System`Null").unwrap();
+    assert_eq!(
+        link.get_expr_with(&config),
+        Ok(Expr::symbol(Symbol::new("System`Null")))
+    );
+}
+
+#[test]
+fn test_array_index_and_iter() {
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_f64_array(&[1.0, 2.0, 3.0], &[3]).unwrap();
+    link.put_i64_array(&[1, 2, 3, 4, 5, 6], &[3, 2]).unwrap();
+
+    {
+        let vector = link.get_f64_array().unwrap();
+
+        assert_eq!(vector[2], 3.0);
+        let elements: Vec<f64> = (&vector).into_iter().copied().collect();
+        assert_eq!(elements, vec![1.0, 2.0, 3.0]);
+    }
+
+    let matrix = link.get_i64_array().unwrap();
+
+    assert_eq!(matrix[&[0, 0]], 1);
+    assert_eq!(matrix[&[1, 1]], 4);
+    assert_eq!(matrix[&[2, 0][..]], 5);
+
+    let mut elements = Vec::new();
+    for elem in &matrix {
+        elements.push(*elem);
+    }
+    assert_eq!(elements, vec![1, 2, 3, 4, 5, 6]);
+
+    // Out of bounds indices panic.
+    let result =
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| matrix[&[0, 2]]));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_enter_text_packet() {
+    use wstp::kernel::PacketType;
+
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_enter_text_packet("1 + 1").unwrap();
+
+    assert_eq!(link.next_packet().unwrap(), PacketType::EnterText);
+    assert_eq!(link.get_string().unwrap(), "1 + 1");
+
+    assert_eq!(PacketType::from_raw(sys::RETURNTEXTPKT), PacketType::ReturnText);
+    assert_eq!(PacketType::from_raw(200), PacketType::Other(200));
+}
+
+#[test]
+fn test_get_return_value() {
+    let mut link = Link::new_loopback().unwrap();
+
+    // Simulate the packets sent by a kernel in response to an EvaluatePacket.
+    link.put_function("System`InputNamePacket", 1).unwrap();
+    link.put_str("In[1]:= ").unwrap();
+    link.end_packet().unwrap();
+
+    link.put_function("System`ReturnPacket", 1).unwrap();
+    link.put_i64(2).unwrap();
+    link.end_packet().unwrap();
+
+    assert_eq!(link.get_return_value().unwrap(), Expr::from(2));
+
+    // Symbols sent without a context are resolved in System`.
+    link.put_function("ReturnPacket", 1).unwrap();
+    link.put_function("List", 1).unwrap();
+    link.put_symbol("True").unwrap();
+    link.end_packet().unwrap();
+
+    assert_eq!(
+        link.get_return_value().unwrap(),
+        Expr::list(vec![Expr::symbol(Symbol::new("System`True"))])
+    );
+
+    // A result sent as text is not a return value.
+    link.put_function("System`ReturnTextPacket", 1).unwrap();
+    link.put_str("2").unwrap();
+    link.end_packet().unwrap();
+
+    assert!(link.get_return_value().is_err());
+}
+
+#[test]
+fn test_get_f64_coerce() {
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_i64(-3).unwrap();
+    link.put_f64(0.25).unwrap();
+    link.put_str("1.0").unwrap();
+
+    assert_eq!(link.get_f64_coerce(), Ok(-3.0));
+    assert_eq!(link.get_f64_coerce(), Ok(0.25));
+    assert!(link.get_f64_coerce().is_err());
+
+    // The string was not consumed.
+    assert_eq!(link.get_string().unwrap(), "1.0");
+}
+
+#[test]
+fn test_quantity_round_trip() {
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_quantity(-1.5, "Seconds").unwrap();
+
+    // An integer value is coerced to a real.
+    link.put_function("System`Quantity", 2).unwrap();
+    link.put_i64(3).unwrap();
+    link.put_str("Meters").unwrap();
+
+    // Quantities sent by the Kernel use System` symbols without a context.
+    link.put_function("Quantity", 2).unwrap();
+    link.put_f64(0.5).unwrap();
+    link.put_str("Kilograms").unwrap();
+
+    // Quantity[1, "Meters", extra]
+    link.put_function("System`Quantity", 3).unwrap();
+    link.put_i64(1).unwrap();
+    link.put_str("Meters").unwrap();
+    link.put_i64(0).unwrap();
+
+    assert_eq!(link.get_quantity(), Ok((-1.5, "Seconds".to_owned())));
+    assert_eq!(link.get_quantity(), Ok((3.0, "Meters".to_owned())));
+    assert_eq!(link.get_quantity(), Ok((0.5, "Kilograms".to_owned())));
+    assert!(link.get_quantity().is_err());
+}
+
+#[test]
+fn test_get_real_string() {
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_f64(-0.125).unwrap();
+    link.put_i64(1).unwrap();
+
+    assert!(link.get_real_string().unwrap().starts_with("-0.125"));
+
+    // Integers are not reals.
+    assert!(link.get_real_string().is_err());
+    assert_eq!(link.get_i64(), Ok(1));
+}
+
+#[test]
+fn test_peek_integer_bits() {
+    let mut link = Link::new_loopback().unwrap();
+
+    for (value, bits) in [
+        (0, 8),
+        (127, 8),
+        (128, 16),
+        (-128, 8),
+        (-129, 16),
+        (1 << 40, 64),
+        (i64::MIN, 64),
+    ] {
+        link.put_i64(value).unwrap();
+
+        assert_eq!(link.peek_integer_bits(), Ok(Some(bits)), "value: {}", value);
+        assert_eq!(link.get_i64(), Ok(value));
+    }
+
+    link.put_str("127").unwrap();
+
+    assert_eq!(link.peek_integer_bits(), Ok(None));
+    assert_eq!(link.get_string().unwrap(), "127");
+}
+
+#[test]
+fn test_char_round_trip() {
+    let mut link = Link::new_loopback().unwrap();
+
+    for c in ['a', 'é', '∞', '🦀'] {
+        link.put_char(c).unwrap();
+        assert_eq!(link.get_char(), Ok(c));
+    }
+
+    link.put_str("🦀🦀").unwrap();
+    link.put_str("").unwrap();
+
+    assert!(link.get_char().is_err());
+    assert!(link.get_char().is_err());
+}
+
+#[test]
+fn test_expect_schema() {
+    fn put_message(link: &mut Link) -> Result<(), wstp::Error> {
+        link.put_function("Global`Message", 2)?;
+        link.put_str("id")?;
+        link.put_i64(5)
+    }
+
+    let schema = Link::record_schema(put_message).unwrap();
+    assert_eq!(schema.len(), 4);
+
+    let mut link = Link::new_loopback().unwrap();
+
+    put_message(&mut link).unwrap();
+
+    link.expect_schema(&schema).unwrap();
+
+    // The data is still available to read.
+    assert_eq!(link.test_head("Global`Message"), Ok(2));
+    assert_eq!(link.get_string().unwrap(), "id");
+    assert_eq!(link.get_i64(), Ok(5));
+
+    // Write the arguments in the wrong order.
+    link.put_function("Global`Message", 2).unwrap();
+    link.put_i64(5).unwrap();
+    link.put_str("id").unwrap();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        link.expect_schema(&schema)
+    }));
+
+    assert_eq!(result.is_err(), cfg!(debug_assertions));
+}
+
+#[test]
+fn test_error_code() {
+    let mut link = Link::new_loopback().unwrap();
+
+    assert_eq!(link.error_code(), None);
+
+    link.put_str("not an integer").unwrap();
+
+    let err = link.get_i64().unwrap_err();
+
+    assert!(err.code().is_some());
+    assert_eq!(link.error_code(), err.code());
+
+    link.clear_error();
+
+    assert_eq!(link.error_code(), None);
+}
+
+#[test]
+fn test_get_numeric_array() {
+    use wstp::NumericArrayData;
+
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_function("System`NumericArray", 2).unwrap();
+    link.put_f64_array(&[0.5, 1.5, 2.5], &[3]).unwrap();
+    link.put_str("Real32").unwrap();
+
+    let array = link.get_numeric_array().unwrap();
+    assert_eq!(array.type_name(), "Real32");
+    assert_eq!(array.dimensions(), &[3]);
+    assert_eq!(array.into_data(), NumericArrayData::Real32(vec![0.5, 1.5, 2.5]));
+
+    link.put_function("System`NumericArray", 2).unwrap();
+    link.put_i64_array(&[0, 255, 256, 65535], &[2, 2]).unwrap();
+    link.put_str("UnsignedInteger16").unwrap();
+
+    let array = link.get_numeric_array().unwrap();
+    assert_eq!(array.rank(), 2);
+    assert_eq!(
+        array.data(),
+        &NumericArrayData::UnsignedInteger16(vec![0, 255, 256, 65535])
+    );
+
+    // Out of range elements are an error.
+    link.put_function("System`NumericArray", 2).unwrap();
+    link.put_i64_array(&[-1], &[1]).unwrap();
+    link.put_str("UnsignedInteger8").unwrap();
+
+    assert!(link.get_numeric_array().is_err());
+}
+
+#[test]
+fn test_get_numeric_array_from_kernel() {
+    use wstp::NumericArrayData;
+
+    let mut link = Link::new_loopback().unwrap();
+
+    // NumericArrays sent by the Kernel use System` symbols without a context.
+    link.put_function("NumericArray", 2).unwrap();
+    link.put_i64_array(&[-1, 2], &[2]).unwrap();
+    link.put_str("Integer32").unwrap();
+
+    let array = link.get_numeric_array().unwrap();
+    assert_eq!(array.data(), &NumericArrayData::Integer32(vec![-1, 2]));
+
+    // UnsignedInteger64 elements greater than i64::MAX can be read.
+    let big: &[u8] = b"18446744073709551615";
+
+    link.put_function("NumericArray", 2).unwrap();
+    link.put_function("List", 2).unwrap();
+    link.put_i64(1).unwrap();
+    unsafe {
+        let raw_link = link.raw_link();
+        let len = big.len() as i32;
+        assert_ne!(sys::WSPutNext(raw_link, sys::WSTKINT as i32), 0);
+        assert_ne!(sys::WSPutSize(raw_link, len), 0);
+        assert_ne!(sys::WSPutData(raw_link, big.as_ptr().cast(), len), 0);
+    }
+    link.put_str("UnsignedInteger64").unwrap();
+
+    let array = link.get_numeric_array().unwrap();
+    assert_eq!(array.dimensions(), &[2]);
+    assert_eq!(
+        array.data(),
+        &NumericArrayData::UnsignedInteger64(vec![1, u64::MAX])
+    );
+
+    // Real elements are not rounded to the declared integer type.
+    link.put_function("NumericArray", 2).unwrap();
+    link.put_f64_array(&[1.5], &[1]).unwrap();
+    link.put_str("Integer8").unwrap();
+
+    assert!(link.get_numeric_array().is_err());
+}
+
+#[test]
+fn test_sparse_array() {
+    let mut link = Link::new_loopback().unwrap();
+
+    let rules = [((0, 1), 1.5), ((2, 0), -2.0)];
+
+    link.put_sparse_array(&rules, (3, 2)).unwrap();
+
+    assert_eq!(
+        link.get_sparse_array(),
+        Ok((vec![(0, 1), (2, 0)], vec![1.5, -2.0], (3, 2)))
+    );
+
+    // Out of bounds indices are an error, and nothing is written.
+    assert!(link.put_sparse_array(&[((3, 0), 1.0)], (3, 2)).is_err());
+    assert!(!link.is_ready());
+
+    // The form of SparseArray sent by the Kernel, equivalent to `rules` above.
+    let ints = |ints: &[i64]| Expr::list(ints.iter().copied().map(Expr::from).collect());
+
+    let raw = Expr::normal(Symbol::new("System`SparseArray"), vec![
+        Expr::symbol(Symbol::new("System`Automatic")),
+        ints(&[3, 2]),
+        Expr::real(0.0),
+        Expr::list(vec![
+            Expr::from(1),
+            Expr::list(vec![
+                ints(&[0, 1, 1, 2]),
+                Expr::list(vec![ints(&[2]), ints(&[1])]),
+            ]),
+            Expr::list(vec![Expr::real(1.5), Expr::from(-2)]),
+        ]),
+    ]);
+
+    link.put_expr(&raw).unwrap();
+
+    assert_eq!(
+        link.get_sparse_array(),
+        Ok((vec![(0, 1), (2, 0)], vec![1.5, -2.0], (3, 2)))
+    );
+
+    // Symbols without a context, as sent by the Kernel, are resolved in System`.
+    link.put_function("SparseArray", 2).unwrap();
+    link.put_function("List", 1).unwrap();
+    link.put_function("Rule", 2).unwrap();
+    link.put_i64_array(&[1, 1], &[2]).unwrap();
+    link.put_i64(3).unwrap();
+    link.put_i64_array(&[1, 1], &[2]).unwrap();
+
+    assert_eq!(link.get_sparse_array(), Ok((vec![(0, 0)], vec![3.0], (1, 1))));
+}
+
+#[test]
+fn test_i64_array_column_major() {
+    use wstp::ArrayOrder;
+
+    let mut link = Link::new_loopback().unwrap();
+
+    // The 2x3 matrix {{1, 2, 3}, {4, 5, 6}}, in column-major order.
+    let column_major = [1, 4, 2, 5, 3, 6];
+
+    link.put_i64_array_with_order(&column_major, &[2, 3], ArrayOrder::ColumnMajor)
+        .unwrap();
+
+    {
+        let array = link.get_i64_array().unwrap();
+
+        assert_eq!(array.data(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(array.dimensions(), &[2, 3]);
+    }
+
+    // Round trip a rank 3 array.
+    let data: Vec<i64> = (0..24).collect();
+
+    link.put_i64_array_with_order(&data, &[2, 3, 4], ArrayOrder::ColumnMajor)
+        .unwrap();
+
+    assert_eq!(
+        link.get_i64_array_with_order(ArrayOrder::ColumnMajor),
+        Ok((data.clone(), vec![2, 3, 4]))
+    );
+
+    link.put_i64_array_with_order(&data, &[2, 3, 4], ArrayOrder::RowMajor)
+        .unwrap();
+
+    assert_eq!(link.get_i64_array().unwrap().data(), data.as_slice());
+}
+
+#[test]
+fn test_get_f64_array_streaming() {
+    let mut link = Link::new_loopback().unwrap();
+
+    let len = Link::STREAMING_CHUNK_LEN * 2 + 10;
+    let data: Vec<f64> = (0..len).map(|i| i as f64).collect();
+
+    link.put_f64_array(&data, &[2, len / 2]).unwrap();
+
+    let mut chunk_lens: Vec<usize> = Vec::new();
+    let mut read: Vec<f64> = Vec::new();
+
+    let dimensions = link
+        .get_f64_array_streaming(|chunk| {
+            chunk_lens.push(chunk.len());
+            read.extend_from_slice(chunk);
+        })
+        .unwrap();
+
+    assert_eq!(dimensions, vec![2, len / 2]);
+    assert_eq!(read, data);
+    assert_eq!(
+        chunk_lens,
+        vec![Link::STREAMING_CHUNK_LEN, Link::STREAMING_CHUNK_LEN, 10]
+    );
+
+    // Empty and ragged arrays.
+    link.put_expr(&Expr::list(vec![])).unwrap();
+    assert_eq!(link.get_f64_array_streaming(|_| panic!()), Ok(vec![0]));
+
+    let ragged = Expr::list(vec![
+        Expr::list(vec![Expr::from(1)]),
+        Expr::list(vec![Expr::from(2), Expr::from(3)]),
+    ]);
+
+    link.put_expr(&ragged).unwrap();
+    assert!(link.get_f64_array_streaming(|_| ()).is_err());
+
+    // Heads other than List are an error.
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_expr(&Expr::normal(Symbol::new("System`Sequence"), vec![Expr::from(1)]))
+        .unwrap();
+    assert!(link.get_f64_array_streaming(|_| panic!()).is_err());
+}
+
+#[test]
+fn test_string_array_round_trip() {
+    let mut link = Link::new_loopback().unwrap();
+
+    let data = ["α", "", "two words", "🦀", "e", "f"];
+
+    link.put_string_array(&data, &[2, 3]).unwrap();
+
+    assert_eq!(
+        link.get_string_array(),
+        Ok((data.iter().map(|s| s.to_string()).collect(), vec![2, 3]))
+    );
+
+    // Mismatched dimensions are an error, and nothing is written.
+    assert!(link.put_string_array(&data, &[4, 2]).is_err());
+    assert!(!link.is_ready());
+
+    // Arrays with context-less `List` heads are read.
+    link.put_function("List", 1).unwrap();
+    link.put_str("a").unwrap();
+    assert_eq!(link.get_string_array(), Ok((vec!["a".to_owned()], vec![1])));
+
+    // Non-string elements are an error.
+    link.put_function("System`List", 2).unwrap();
+    link.put_str("a").unwrap();
+    link.put_i64(1).unwrap();
+    assert_eq!(
+        link.get_string_array().unwrap_err().to_string(),
+        "WSTP error: get_string_array: array element is not a string: 1"
+    );
+}
+
+#[test]
+fn test_io_error_conversion() {
+    use std::io;
+
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_str("not an integer").unwrap();
+
+    let err = link.get_i64().unwrap_err();
+    let message = err.to_string();
+
+    let io_err = io::Error::from(err);
+    assert_eq!(io_err.kind(), io::ErrorKind::Other);
+    assert_eq!(io_err.to_string(), message);
+
+    let err = wstp::Error::from(io::Error::new(io::ErrorKind::NotFound, "no file"));
+    assert_eq!(err.code(), None);
+    assert_eq!(err.to_string(), "WSTP error: I/O error: no file");
+}
+
+#[test]
+fn test_copy_loopback_to() {
+    let mut source = Link::new_loopback().unwrap();
+
+    source.put_i64(0).unwrap();
+    source.put_i64(1).unwrap();
+    source.put_function("System`List", 1).unwrap();
+    source.put_i64(2).unwrap();
+
+    // Only the data after the current read position is copied.
+    assert_eq!(source.get_i64(), Ok(0));
+
+    let mut dests: Vec<Link> = (0..3).map(|_| Link::new_loopback().unwrap()).collect();
+
+    for dest in &mut dests {
+        source.copy_loopback_to(dest).unwrap();
+    }
+
+    let expected = vec![
+        Expr::from(1),
+        Expr::normal(Symbol::new("System`List"), vec![Expr::from(2)]),
+    ];
+
+    for dest in &mut dests {
+        assert_eq!(dest.get_all_exprs(), Ok(expected.clone()));
+    }
+
+    assert_eq!(source.get_all_exprs(), Ok(expected));
+}
+
+#[test]
+fn test_snapshot_loopback() {
+    let mut source = Link::new_loopback().unwrap();
+
+    source.put_function("System`List", 2).unwrap();
+    source.put_i64(1).unwrap();
+    source.put_str("two").unwrap();
+
+    let mut snapshot = source.snapshot_loopback().unwrap();
+
+    assert_eq!(snapshot.content_eq(&mut source), Ok(true));
+
+    // Reading from the snapshot does not affect the source.
+    assert_eq!(snapshot.test_head("System`List"), Ok(2));
+    assert_eq!(snapshot.get_i64(), Ok(1));
+
+    assert_eq!(source.peek_count(), Ok(1));
+
+    // Writing to the source does not affect the snapshot.
+    source.put_i64(3).unwrap();
+
+    assert_eq!(snapshot.get_string().unwrap(), "two");
+    assert!(!snapshot.is_ready());
+}
+
+#[test]
+fn test_content_eq() {
+    let mut a = Link::new_loopback().unwrap();
+    let mut b = Link::new_loopback().unwrap();
+
+    assert_eq!(a.content_eq(&mut b), Ok(true));
+
+    a.put_function("Global`f", 1).unwrap();
+    a.put_str("x").unwrap();
+
+    b.put_expr(&Expr::normal(Symbol::new("Global`f"), vec![Expr::string("x")]))
+        .unwrap();
+
+    assert_eq!(a.content_eq(&mut b), Ok(true));
+
+    a.put_f64(1.0).unwrap();
+    b.put_i64(1).unwrap();
+
+    assert_eq!(a.content_eq(&mut b), Ok(false));
+
+    // Both links still contain their expressions.
+    assert_eq!(a.peek_count(), Ok(2));
+    assert_eq!(b.peek_count(), Ok(2));
+}
+
+#[test]
+fn test_with_borrowed() {
+    let mut link = Link::new_loopback().unwrap();
+
+    link.put_i64(1).unwrap();
+    link.put_i64(2).unwrap();
+
+    let mut raw_link = unsafe { link.raw_link() };
+
+    // Each borrow reads one value, and leaves the link open when it ends.
+    for expected in [1, 2] {
+        let value =
+            unsafe { Link::with_borrowed(&mut raw_link, |borrowed| borrowed.get_i64()) };
+
+        assert_eq!(value, Ok(expected));
+    }
+
+    // Data written through a borrowed link is visible to the owner.
+    unsafe {
+        Link::with_borrowed(&mut raw_link, |borrowed| {
+            assert!(borrowed.is_loopback());
+            borrowed.put_str("borrowed").unwrap();
+        })
+    };
+
+    assert_eq!(link.get_string().unwrap(), "borrowed");
+    assert!(!link.is_ready());
+}
+
+#[test]
+fn test_read_depth() {
+    let mut link = Link::new_loopback().unwrap();
+
+    // Put {f[1, g[]], 2}, followed by 3.
+    link.put_function("System`List", 2).unwrap();
+    link.put_function("Global`f", 2).unwrap();
+    link.put_i64(1).unwrap();
+    link.put_function("Global`g", 0).unwrap();
+    link.put_i64(2).unwrap();
+    link.put_i64(3).unwrap();
+
+    // Read the outer function one token at a time.
+    assert!(matches!(
+        link.get_token(),
+        Ok(Token::Function { length: 2 })
+    ));
+    assert_eq!(link.read_depth(), 1);
+    assert_eq!(link.get_symbol().unwrap().as_str(), "System`List");
+    assert_eq!(link.read_depth(), 1);
+
+    // Read f[..] using test_head().
+    assert_eq!(link.test_head("Global`f"), Ok(2));
+    assert_eq!(link.read_depth(), 2);
+    assert_eq!(link.get_i64(), Ok(1));
+
+    // Reading the empty g[] completes f[..], but not the outer List.
+    assert_eq!(link.test_head("Global`g"), Ok(0));
+    assert_eq!(link.read_depth(), 1);
+
+    assert_eq!(link.get_i64(), Ok(2));
+    assert_eq!(link.read_depth(), 0);
+
+    assert_eq!(link.get_i64(), Ok(3));
+    assert_eq!(link.read_depth(), 0);
+
+    // Reading a complete expression leaves the depth unchanged.
+    link.put_function("System`List", 1).unwrap();
+    link.put_function("System`List", 2).unwrap();
+    link.put_i64(4).unwrap();
+    link.put_i64(5).unwrap();
+
+    assert_eq!(link.test_head("System`List"), Ok(1));
+    assert_eq!(link.read_depth(), 1);
+    assert!(link.get_expr().is_ok());
+    assert_eq!(link.read_depth(), 0);
+
+    // Depths are per-link.
+    let other = Link::new_loopback().unwrap();
+    assert_eq!(other.read_depth(), 0);
+}